@@ -6,6 +6,11 @@ pub enum ECSError {
     ArrayResourceWriteOutOfBoundsError,
     // Archtype Errors
     EntityIdInUseError,
+    EntityIdOutOfRangeError,
+    ArchetypeFull,
+    // System Errors
+    SystemNotFoundError,
+    SystemNameInUseError,
 }
 
 impl Display for ECSError {
@@ -17,6 +22,18 @@ impl Display for ECSError {
             ECSError::EntityIdInUseError => {
                 write!(f, "Attempted to create an entity with an already in use Id.")
             },
+            ECSError::EntityIdOutOfRangeError => {
+                write!(f, "Attempted to create an entity with an Id above `ECS_MAX_ENTITY_ID`.")
+            },
+            ECSError::ArchetypeFull => {
+                write!(f, "Attempted to create an entity in a full fixed capacity archetype.")
+            },
+            ECSError::SystemNotFoundError => {
+                write!(f, "Attempted to access a system that is not declared.")
+            },
+            ECSError::SystemNameInUseError => {
+                write!(f, "Attempted to add a system with an already in use name.")
+            },
         }
     }
 }
@@ -29,337 +46,3419 @@ pub enum ECSEntityCreateConflictResolution {
     Ignore
 }
 
-#[macro_export]
-macro_rules! create_ecs {
-    (
-        // ECS Struct name
-        $name:ident,
-        // ECS Required resources
-        Resources(
-            $(
-                $resource_name:ident => $resource_ty:ty
-            ),*
-        ),
-        // ECS Array resources
-        ArrayResources(
-            $(
-                $arr_resource_name:ident => [$arr_resource_ty:ty; $arr_resource_len:literal]
-            ),*
-        ),
-        // ECS Map resources
-        MapResources(
-            $(
-                $map_resource_name:ident => <$map_resource_key_type:ty, $map_resource_value_type:ty>
-            ),*
-        ),
-        // ECS Optional resources
-        OptionalResources(
-            $(
-                $opt_resource_name:ident => $opt_resource_ty:ty
-            ),*
-        ),
-        // ECS Entity Archtypes
-        Archtypes(
-            $(
-                Entity(
-                    $entity_name:ident,
-                    // ECS Entity archtype Components
-                    Components(
-                        $(
-                            $comp_name:ident => $comp_type:ty
-                        ),*
-                    )
-                )
-            ),+
-        )
-    ) => {
-        paste::paste! { 
-        mod id {
-            $(
-                #[derive(Clone, Debug)]
-                pub struct [<$entity_name:camel EntityId>] {
-                    id: usize,
-                    generation: usize,
-                    valid: bool
+/// Access to a resource of an ECS through the marker type `M`
+/// generated for it, so code can be generic over any ECS that has
+/// the resource.
+pub trait ECSResource<M> {
+    type Value;
+
+    /// Gets the value of the resource
+    fn get(&self) -> &Self::Value;
+
+    /// Gets the mutable value of the resource
+    fn get_mut(&mut self) -> &mut Self::Value;
+
+    /// Writes a value to the resource
+    fn set(&mut self, value: Self::Value);
+}
+
+/// Access to the events of an ECS through the marker type `M`
+/// generated for them.
+pub trait ECSEvent<M> {
+    type Value;
+
+    /// Gets the queue of the events
+    fn events(&self) -> &ECSEvents<Self::Value>;
+}
+
+/// Access to the entities of an archetype of an ECS through the
+/// marker type `M` generated for it.
+pub trait ECSArchetype<M> {
+    type Id: 'static;
+    type View<'a> where Self: 'a;
+
+    /// Iterate over the Ids of the entities
+    fn ids(&self) -> impl Iterator<Item = &Self::Id>;
+
+    /// Gets the view of the components of the entity of Id
+    fn view(&self, id: &Self::Id) -> Option<Self::View<'_>>;
+}
+
+/// Lifecycle events of entities that observers can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ECSEntityEvent {
+    Spawned,
+    Deleted
+}
+
+/// Lifecycle events of components that observers can subscribe to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ECSComponentEvent {
+    Added,
+    Changed,
+    Removed
+}
+
+/// Schedules the stages of systems run in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ECSSchedule {
+    /// Stages run once before the first `run_systems`
+    Startup,
+    /// Stages run by every `run_systems`
+    Update,
+    /// Stages run by `shutdown`
+    Shutdown
+}
+
+/// Description of a system in the registry of an ECS
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ECSSystemInfo {
+    /// Name the system is declared with
+    pub name: &'static str,
+    /// Schedule of the stage of the system
+    pub schedule: ECSSchedule,
+    /// Index of the stage of the system in the Systems clause
+    pub stage: usize,
+    /// Whether the system takes `&mut` of the ECS and runs alone
+    pub exclusive: bool,
+    /// Names the system declares to read
+    pub reads: &'static [&'static str],
+    /// Names the system declares to write
+    pub writes: &'static [&'static str],
+    /// Whether the system runs with its stage
+    pub enabled: bool
+}
+
+/// Error of a failed system
+pub type ECSSystemError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Value returned by a system, either `()` or a `Result` whose error
+/// converts into [`ECSSystemError`], such as `ECSError`
+pub trait ECSSystemOutput {
+    /// Converts the value into the result of the system
+    fn into_result(self) -> Result<(), ECSSystemError>;
+}
+
+impl ECSSystemOutput for () {
+    fn into_result(self) -> Result<(), ECSSystemError> {
+        Ok(())
+    }
+}
+
+impl<E: Into<ECSSystemError>> ECSSystemOutput for Result<(), E> {
+    fn into_result(self) -> Result<(), ECSSystemError> {
+        self.map_err(Into::into)
+    }
+}
+
+/// What a schedule does when one of its systems fails
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ECSErrorPolicy {
+    /// Stops the schedule after the stage of the failed system
+    #[default]
+    Stop,
+    /// Runs the rest of the schedule
+    Continue,
+    /// Prints the failure to stderr and runs the rest of the schedule
+    Log
+}
+
+/// Failure of a system while running a schedule
+#[derive(Debug)]
+pub struct ECSSystemFailure {
+    /// Name of the failed system
+    pub system: &'static str,
+    /// Error returned by the system or by applying its Commands
+    pub error: ECSSystemError,
+    /// System tick the system failed on
+    pub tick: usize
+}
+
+/// Report of the systems that failed while running schedules
+#[derive(Debug, Default)]
+#[must_use]
+pub struct ECSSystemReport {
+    pub failures: Vec<ECSSystemFailure>
+}
+
+impl ECSSystemReport {
+    /// Checks if no system failed
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Number of runs the average duration of a system is rolled over
+#[cfg(feature = "system-stats")]
+const ECS_SYSTEM_STATS_WINDOW: u64 = 64;
+
+/// Call statistics of a system.
+/// 
+/// Statistics are only recorded with the `system-stats` feature,
+/// without it this is empty and recording does nothing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ECSSystemStats {
+    /// Number of runs
+    #[cfg(feature = "system-stats")]
+    pub calls: u64,
+    /// Duration of the last run
+    #[cfg(feature = "system-stats")]
+    pub last: std::time::Duration,
+    /// Average duration, rolled over the last runs
+    #[cfg(feature = "system-stats")]
+    pub average: std::time::Duration,
+    /// Longest duration of a run
+    #[cfg(feature = "system-stats")]
+    pub max: std::time::Duration
+}
+
+impl ECSSystemStats {
+    /// Records the run of system timed by timer, printing a warning
+    /// to stderr if it took longer than budget
+    #[doc(hidden)]
+    #[allow(unused_variables)]
+    pub fn record(&mut self, timer: ECSSystemTimer, system: &str, tick: usize, budget: Option<std::time::Duration>) {
+        #[cfg(feature = "system-stats")]
+        {
+            let duration = timer.started.elapsed();
+            self.calls += 1;
+            self.last = duration;
+            let window = self.calls.min(ECS_SYSTEM_STATS_WINDOW) as u32;
+            self.average = self.average - self.average / window + duration / window;
+            self.max = self.max.max(duration);
+            if let Some(budget) = budget.filter(|budget| duration > *budget) {
+                eprintln!("System `{system}` took {duration:?} on tick {tick}, over its budget of {budget:?}.");
+            }
+        }
+    }
+
+    /// Formats the statistics of systems as a table
+    #[cfg(feature = "system-stats")]
+    pub fn table(systems: &[(&str, ECSSystemStats)]) -> String {
+        let width = systems.iter().map(|(system, _)| system.len()).fold("System".len(), usize::max);
+        let mut table = format!("{:<width$} | {:>8} | {:>12} | {:>12} | {:>12}\n", "System", "Calls", "Last", "Average", "Max");
+        for (system, stats) in systems {
+            table.push_str(&format!(
+                "{:<width$} | {:>8} | {:>12} | {:>12} | {:>12}\n",
+                system,
+                stats.calls,
+                format!("{:?}", stats.last),
+                format!("{:?}", stats.average),
+                format!("{:?}", stats.max)
+            ));
+        }
+        table
+    }
+}
+
+/// Timer of a system run, recorded to [`ECSSystemStats`].
+/// 
+/// Does nothing without the `system-stats` feature.
+#[doc(hidden)]
+pub struct ECSSystemTimer {
+    #[cfg(feature = "system-stats")]
+    started: std::time::Instant
+}
+
+impl ECSSystemTimer {
+    /// Starts timing a system run
+    pub fn start() -> Self {
+        ECSSystemTimer {
+            #[cfg(feature = "system-stats")]
+            started: std::time::Instant::now()
+        }
+    }
+}
+
+/// Parameter of a runtime system, extracted from the ECS `W`
+/// on every run.
+pub trait ECSSystemParam<W> {
+    /// State the system keeps for the parameter between runs
+    type State: 'static;
+    /// Parameter passed to the system
+    type Item<'w> where W: 'w;
+
+    /// Creates the state when the system is added
+    fn init(ecs: &W) -> Self::State;
+
+    /// Extracts the parameter from the ECS
+    fn fetch<'w>(ecs: &'w W, state: &'w mut Self::State) -> Self::Item<'w>;
+
+    /// Applies what was recorded to state after the system ran
+    fn apply(_ecs: &mut W, _state: &mut Self::State) -> Result<(), ECSError> {
+        Ok(())
+    }
+
+    /// Drops what was recorded to state after the system failed
+    fn discard(_ecs: &W, _state: &mut Self::State) {}
+}
+
+/// Resource parameter of a runtime system, reading the resource
+/// of marker `M`
+pub struct ECSRes<'w, W: ECSResource<M>, M> {
+    value: &'w W::Value,
+    marker: std::marker::PhantomData<fn() -> M>
+}
+
+impl<W: ECSResource<M>, M> std::ops::Deref for ECSRes<'_, W, M> {
+    type Target = W::Value;
+
+    fn deref(&self) -> &W::Value {
+        self.value
+    }
+}
+
+impl<W: ECSResource<M> + 'static, M: 'static> ECSSystemParam<W> for ECSRes<'_, W, M> {
+    type State = ();
+    type Item<'w> = ECSRes<'w, W, M>;
+
+    fn init(_ecs: &W) {}
+
+    fn fetch<'w>(ecs: &'w W, _state: &'w mut ()) -> ECSRes<'w, W, M> {
+        ECSRes {
+            value: ecs.get(),
+            marker: std::marker::PhantomData
+        }
+    }
+}
+
+/// Query parameter of a runtime system, reading the entities of
+/// the archetype of marker `M`
+pub struct ECSQuery<'w, W, M> {
+    ecs: &'w W,
+    marker: std::marker::PhantomData<fn() -> M>
+}
+
+impl<'w, W: ECSArchetype<M>, M> ECSQuery<'w, W, M> {
+    /// Gets the view of the components of the entity of Id
+    pub fn get(&self, id: &W::Id) -> Option<W::View<'w>> {
+        self.ecs.view(id)
+    }
+
+    /// Iterate over the Ids and views of the entities
+    pub fn iter(&self) -> impl Iterator<Item = (&'w W::Id, W::View<'w>)> {
+        let ecs = self.ecs;
+        ecs.ids().filter_map(move |id| Some((id, ecs.view(id)?)))
+    }
+}
+
+impl<W: ECSArchetype<M> + 'static, M: 'static> ECSSystemParam<W> for ECSQuery<'_, W, M> {
+    type State = ();
+    type Item<'w> = ECSQuery<'w, W, M>;
+
+    fn init(_ecs: &W) {}
+
+    fn fetch<'w>(ecs: &'w W, _state: &'w mut ()) -> ECSQuery<'w, W, M> {
+        ECSQuery {
+            ecs,
+            marker: std::marker::PhantomData
+        }
+    }
+}
+
+/// Event reader parameter of a runtime system, reading the events
+/// of marker `M` the system has not seen yet
+pub struct ECSReader<'w, W: ECSEvent<M>, M> {
+    events: &'w ECSEvents<W::Value>,
+    reader: &'w mut ECSEventReader<W::Value>
+}
+
+impl<'w, W: ECSEvent<M>, M> ECSReader<'w, W, M> {
+    /// Reads the events not yet seen by the system
+    pub fn read(&mut self) -> impl Iterator<Item = &'w W::Value> {
+        self.events.read(self.reader)
+    }
+}
+
+impl<W: ECSEvent<M> + 'static, M: 'static> ECSSystemParam<W> for ECSReader<'_, W, M> {
+    type State = ECSEventReader<W::Value>;
+    type Item<'w> = ECSReader<'w, W, M>;
+
+    fn init(ecs: &W) -> ECSEventReader<W::Value> {
+        ecs.events().reader()
+    }
+
+    fn fetch<'w>(ecs: &'w W, state: &'w mut ECSEventReader<W::Value>) -> ECSReader<'w, W, M> {
+        ECSReader {
+            events: ecs.events(),
+            reader: state
+        }
+    }
+}
+
+/// Function that can be added to the ECS `W` as a runtime system,
+/// taking the parameters `P` extracted through [`ECSSystemParam`]
+pub trait ECSSystemFn<W, P>: 'static {
+    /// States of the parameters
+    type State: 'static;
+
+    /// Creates the states of the parameters
+    fn init(ecs: &W) -> Self::State;
+
+    /// Runs the system with the parameters extracted from the ECS
+    fn run(&mut self, ecs: &W, state: &mut Self::State) -> Result<(), ECSSystemError>;
+
+    /// Applies what the parameters recorded while the system ran
+    fn apply(ecs: &mut W, state: &mut Self::State) -> Result<(), ECSError>;
+
+    /// Drops what the parameters recorded while the system failed
+    fn discard(ecs: &W, state: &mut Self::State);
+}
+
+macro_rules! impl_ecs_system_fn {
+    ($($param:ident $state:ident),*) => {
+        impl<W, F, O, $($param),*> ECSSystemFn<W, ($($param,)*)> for F
+        where
+            W: 'static,
+            F: FnMut($($param),*) -> O + 'static,
+            F: for<'w> FnMut($($param::Item<'w>),*) -> O,
+            O: ECSSystemOutput,
+            $($param: ECSSystemParam<W>,)*
+        {
+            type State = ($($param::State,)*);
+
+            #[allow(clippy::unused_unit)]
+            fn init(_ecs: &W) -> Self::State {
+                ($($param::init(_ecs),)*)
+            }
+
+            fn run(&mut self, _ecs: &W, state: &mut Self::State) -> Result<(), ECSSystemError> {
+                // Calling through a function with plain parameters picks the
+                // signature taking the extracted parameters
+                #[allow(clippy::too_many_arguments)]
+                fn call<O, $($param),*>(mut system: impl FnMut($($param),*) -> O, $($state: $param),*) -> O {
+                    system($($state),*)
+                }
+                let ($($state,)*) = state;
+                call(self, $($param::fetch(_ecs, $state)),*).into_result()
+            }
+
+            fn apply(_ecs: &mut W, state: &mut Self::State) -> Result<(), ECSError> {
+                let ($($state,)*) = state;
+                $($param::apply(_ecs, $state)?;)*
+                Ok(())
+            }
+
+            fn discard(_ecs: &W, state: &mut Self::State) {
+                let ($($state,)*) = state;
+                $($param::discard(_ecs, $state);)*
+            }
+        }
+    };
+}
+
+impl_ecs_system_fn!();
+impl_ecs_system_fn!(P0 s0);
+impl_ecs_system_fn!(P0 s0, P1 s1);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2, P3 s3);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2, P3 s3, P4 s4);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2, P3 s3, P4 s4, P5 s5);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2, P3 s3, P4 s4, P5 s5, P6 s6);
+impl_ecs_system_fn!(P0 s0, P1 s1, P2 s2, P3 s3, P4 s4, P5 s5, P6 s6, P7 s7);
+
+/// Run of a runtime system on the ECS `W`
+type ECSRuntimeSystemRun<W> = Box<dyn FnMut(&mut W) -> Result<(), ECSSystemError> + Send + Sync>;
+
+/// System added to the ECS `W` at runtime with `add_system`
+pub struct ECSRuntimeSystem<W> {
+    /// Name the system is added with
+    pub name: &'static str,
+    /// Whether the system runs
+    pub enabled: bool,
+    /// Call statistics of the system
+    pub stats: ECSSystemStats,
+    run: ECSRuntimeSystemRun<W>
+}
+
+impl<W: 'static> ECSRuntimeSystem<W> {
+    /// Creates a runtime system of name, creating the states of the
+    /// parameters of system from the ECS
+    pub fn new<P, F>(name: &'static str, ecs: &W, mut system: F) -> Self
+    where
+        F: ECSSystemFn<W, P> + Send + Sync,
+        F::State: Send + Sync
+    {
+        let mut state = F::init(ecs);
+        ECSRuntimeSystem {
+            name,
+            enabled: true,
+            stats: ECSSystemStats::default(),
+            run: Box::new(move |ecs| {
+                if let Err(error) = system.run(ecs, &mut state) {
+                    F::discard(ecs, &mut state);
+                    return Err(error);
+                }
+                F::apply(ecs, &mut state).map_err(Into::into)
+            })
+        }
+    }
+
+    /// Runs the system, applying what its parameters recorded
+    pub fn run(&mut self, ecs: &mut W) -> Result<(), ECSSystemError> {
+        (self.run)(ecs)
+    }
+}
+
+/// Largest entity id.
+/// 
+/// Creating an entity with an id above it fails with
+/// `ECSError::EntityIdOutOfRangeError`.
+pub const ECS_MAX_ENTITY_ID: usize = u32::MAX as usize;
+
+/// Allocator of entity ids.
+/// 
+/// Clones share the same counter and released ids, so ids can be
+/// reserved without mutable access to the ECS.
+#[derive(Clone, Default)]
+pub struct ECSIdAllocator {
+    next: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    released: std::sync::Arc<std::sync::Mutex<Vec<(usize, usize)>>>,
+    limit: Option<usize>
+}
+
+impl ECSIdAllocator {
+    /// Creates an allocator starting at id 0
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an allocator starting at id 0 with room for at least
+    /// `capacity` released ids
+    pub fn with_capacity(capacity: usize) -> Self {
+        ECSIdAllocator {
+            next: Default::default(),
+            released: std::sync::Arc::new(std::sync::Mutex::new(Vec::with_capacity(capacity))),
+            limit: None
+        }
+    }
+
+    /// Creates an allocator of the ids below `capacity`.
+    /// 
+    /// Every id below `capacity` starts released, so they are reserved
+    /// in order, and ids at or above it are never released.
+    pub fn fixed(capacity: usize) -> Self {
+        ECSIdAllocator {
+            next: std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(capacity)),
+            released: std::sync::Arc::new(std::sync::Mutex::new(
+                (0..capacity).rev().map(|id| (id, 0)).collect()
+            )),
+            limit: Some(capacity)
+        }
+    }
+
+    /// Reserves room for at least `additional` more released ids
+    pub fn reserve_capacity(&self, additional: usize) {
+        self.released().reserve(additional);
+    }
+
+    /// Shrinks the room for released ids as much as possible
+    pub fn shrink_to_fit(&self) {
+        self.released().shrink_to_fit();
+    }
+
+    /// Reserves an id, returning it with the generation of its entity.
+    /// 
+    /// Released ids are reused first, otherwise the id was never
+    /// reserved or marked as used.
+    pub fn reserve(&self) -> (usize, usize) {
+        match self.released().pop() {
+            Some(released) => released,
+            None => (self.next.fetch_add(1, std::sync::atomic::Ordering::Relaxed), 0)
+        }
+    }
+
+    /// Releases `id` to be reserved again with `generation`
+    pub fn release(&self, id: usize, generation: usize) {
+        if self.limit.is_none_or(|limit| id < limit) {
+            self.released().push((id, generation));
+        }
+    }
+
+    /// Marks `id` as used so it is not reserved
+    pub fn mark_used(&self, id: usize) {
+        self.released().retain(|(released, _)| *released != id);
+        self.next.fetch_max(id.saturating_add(1), std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Locks the released ids, recovering the lock if it was poisoned
+    fn released(&self) -> std::sync::MutexGuard<'_, Vec<(usize, usize)>> {
+        self.released.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Double buffered queue of events.
+/// 
+/// Events sent are kept until [`ECSEvents::update`] is called twice,
+/// so if `update` is called once per frame, every event is readable
+/// for the frame it was sent in and the whole frame after that.
+pub struct ECSEvents<T> {
+    previous: Vec<T>,
+    previous_start: usize,
+    current: Vec<T>,
+    current_start: usize
+}
+
+impl<T> ECSEvents<T> {
+    /// Creates an empty event queue
+    pub fn new() -> Self {
+        ECSEvents {
+            previous: vec![],
+            previous_start: 0,
+            current: vec![],
+            current_start: 0
+        }
+    }
+
+    /// Sends an event
+    pub fn send(&mut self, event: T) {
+        self.current.push(event);
+    }
+
+    /// Swaps buffers, dropping events sent before the last update.
+    /// 
+    /// The buffer of the dropped events is reused for the next events.
+    pub fn update(&mut self) {
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+        self.previous_start = self.current_start;
+        self.current_start += self.previous.len();
+    }
+
+    /// Drops all events
+    pub fn clear(&mut self) {
+        self.current_start += self.current.len();
+        self.previous_start = self.current_start;
+        self.previous.clear();
+        self.current.clear();
+    }
+
+    /// Creates a reader that will read all events still in the queue
+    pub fn reader(&self) -> ECSEventReader<T> {
+        ECSEventReader {
+            last: self.previous_start,
+            event: std::marker::PhantomData
+        }
+    }
+
+    /// Reads events not yet seen by `reader`, advancing it
+    pub fn read<'a>(&'a self, reader: &mut ECSEventReader<T>) -> impl Iterator<Item = &'a T> {
+        let previous_skip = reader.last.saturating_sub(self.previous_start).min(self.previous.len());
+        let current_skip = reader.last.saturating_sub(self.current_start).min(self.current.len());
+        reader.last = self.current_start + self.current.len();
+        self.previous[previous_skip..].iter().chain(self.current[current_skip..].iter())
+    }
+}
+
+impl<T> Default for ECSEvents<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cursor of a reader into an [`ECSEvents`] queue
+pub struct ECSEventReader<T> {
+    last: usize,
+    event: std::marker::PhantomData<fn() -> T>
+}
+
+/// Storage of a component by entity id.
+/// 
+/// Every storage strategy selectable in `Components` implements it,
+/// so the generated accessors are the same for all of them.
+pub trait ECSComponentStorage<T>: Default {
+    /// Creates an empty storage with room for at least `capacity` values
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Reserves room for at least `additional` more values
+    fn reserve(&mut self, additional: usize);
+
+    /// Shrinks the storage as much as possible
+    fn shrink_to_fit(&mut self);
+
+    /// Inserts the value for id, returning the value it replaced
+    fn insert(&mut self, id: usize, value: T) -> Option<T>;
+
+    /// Removes the value for id, returning it
+    fn remove(&mut self, id: usize) -> Option<T>;
+
+    /// Gets the value for id
+    fn get(&self, id: usize) -> Option<&T>;
+
+    /// Gets the mutable value for id
+    fn get_mut(&mut self, id: usize) -> Option<&mut T>;
+
+    /// Gets the number of values
+    fn len(&self) -> usize;
+
+    /// Checks if there is a value for id
+    fn contains(&self, id: usize) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Returns `true` if there are no values
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Number of ids in each page of an [`ECSPagedTable`]
+const ECS_PAGE_SIZE: usize = 1024;
+
+/// Table indexed by entity id.
+/// 
+/// The table is allocated in pages, so a large id only allocates
+/// its own page and pages without values can be freed.
+struct ECSPagedTable<T> {
+    pages: Vec<Option<Box<[Option<T>]>>>
+}
+
+impl<T> ECSPagedTable<T> {
+    /// Creates an empty table
+    fn new() -> Self {
+        ECSPagedTable {
+            pages: vec![]
+        }
+    }
+
+    /// Allocates the pages of every id below `ids`
+    fn reserve_ids(&mut self, ids: usize) {
+        let pages = ids.min(ECS_MAX_ENTITY_ID).div_ceil(ECS_PAGE_SIZE);
+        if pages > self.pages.len() {
+            self.pages.resize_with(pages, || None);
+        }
+        for page in &mut self.pages[..pages] {
+            page.get_or_insert_with(Self::empty_page);
+        }
+    }
+
+    /// Frees the pages without values
+    fn shrink_to_fit(&mut self) {
+        for page in &mut self.pages {
+            if page.as_ref().is_some_and(|values| values.iter().all(Option::is_none)) {
+                *page = None;
+            }
+        }
+        let used = self.pages.iter().rposition(Option::is_some).map_or(0, |page| page + 1);
+        self.pages.truncate(used);
+        self.pages.shrink_to_fit();
+    }
+
+    /// Gets the slot of id, allocating its page.
+    /// 
+    /// Panics if id is above [`ECS_MAX_ENTITY_ID`].
+    fn slot(&mut self, id: usize) -> &mut Option<T> {
+        assert!(id <= ECS_MAX_ENTITY_ID, "Id {id} is above `ECS_MAX_ENTITY_ID`.");
+        let page = id / ECS_PAGE_SIZE;
+        if page >= self.pages.len() {
+            self.pages.resize_with(page + 1, || None);
+        }
+        &mut self.pages[page].get_or_insert_with(Self::empty_page)[id % ECS_PAGE_SIZE]
+    }
+
+    /// Takes the value of id out of the table
+    fn take(&mut self, id: usize) -> Option<T> {
+        self.pages.get_mut(id / ECS_PAGE_SIZE)?.as_mut()?[id % ECS_PAGE_SIZE].take()
+    }
+
+    /// Gets the value of id
+    fn get(&self, id: usize) -> Option<&T> {
+        self.pages.get(id / ECS_PAGE_SIZE)?.as_ref()?[id % ECS_PAGE_SIZE].as_ref()
+    }
+
+    /// Gets the mutable value of id
+    fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        self.pages.get_mut(id / ECS_PAGE_SIZE)?.as_mut()?[id % ECS_PAGE_SIZE].as_mut()
+    }
+
+    /// Iterate over the ids and values in id order
+    fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter_map(|(page, values)| values.as_ref().map(|values| (page * ECS_PAGE_SIZE, values)))
+            .flat_map(|(first, values)| {
+                values
+                    .iter()
+                    .enumerate()
+                    .filter_map(move |(offset, value)| value.as_ref().map(|value| (first + offset, value)))
+            })
+    }
+
+    /// Iterate over the ids and mutable values in id order
+    fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.pages
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(page, values)| values.as_mut().map(|values| (page * ECS_PAGE_SIZE, values)))
+            .flat_map(|(first, values)| {
+                values
+                    .iter_mut()
+                    .enumerate()
+                    .filter_map(move |(offset, value)| value.as_mut().map(|value| (first + offset, value)))
+            })
+    }
+
+    /// Creates a page without values
+    fn empty_page() -> Box<[Option<T>]> {
+        (0..ECS_PAGE_SIZE).map(|_| None).collect()
+    }
+}
+
+/// Sparse set storage of a component.
+/// 
+/// A sparse index by entity id points into dense arrays of values
+/// and ids, so adding, removing and getting are O(1) and iteration
+/// is contiguous. Removing swaps the last value into the hole, so
+/// the order of values is not kept.
+/// 
+/// Inserting an id above [`ECS_MAX_ENTITY_ID`] panics.
+pub struct ECSSparseSet<T> {
+    sparse: ECSPagedTable<usize>,
+    dense: Vec<T>,
+    ids: Vec<usize>
+}
+
+impl<T> ECSSparseSet<T> {
+    /// Creates an empty sparse set
+    pub fn new() -> Self {
+        ECSSparseSet {
+            sparse: ECSPagedTable::new(),
+            dense: vec![],
+            ids: vec![]
+        }
+    }
+
+    /// Gets the values as a contiguous slice
+    pub fn values(&self) -> &[T] {
+        &self.dense
+    }
+
+    /// Gets the mutable values as a contiguous slice
+    pub fn values_mut(&mut self) -> &mut [T] {
+        &mut self.dense
+    }
+
+    /// Iterate over the ids and values
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.ids.iter().copied().zip(self.dense.iter())
+    }
+
+    /// Iterate over the ids and mutable values
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.ids.iter().copied().zip(self.dense.iter_mut())
+    }
+
+    /// Gets the index of the value for id in the dense arrays
+    pub fn index(&self, id: usize) -> Option<usize> {
+        self.sparse.get(id).copied()
+    }
+}
+
+impl<T> ECSComponentStorage<T> for ECSSparseSet<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        let mut sparse = ECSPagedTable::new();
+        sparse.reserve_ids(capacity);
+        ECSSparseSet {
+            sparse,
+            dense: Vec::with_capacity(capacity),
+            ids: Vec::with_capacity(capacity)
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Ids are reused, so they are expected to stay below the capacity
+        self.sparse.reserve_ids(self.len().saturating_add(additional));
+        self.dense.reserve(additional);
+        self.ids.reserve(additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.sparse.shrink_to_fit();
+        self.dense.shrink_to_fit();
+        self.ids.shrink_to_fit();
+    }
+
+    fn insert(&mut self, id: usize, value: T) -> Option<T> {
+        if let Some(index) = self.index(id) {
+            return Some(std::mem::replace(&mut self.dense[index], value));
+        }
+        *self.sparse.slot(id) = Some(self.dense.len());
+        self.dense.push(value);
+        self.ids.push(id);
+        None
+    }
+
+    fn remove(&mut self, id: usize) -> Option<T> {
+        let index = self.sparse.take(id)?;
+        self.ids.swap_remove(index);
+        if let Some(moved) = self.ids.get(index) {
+            *self.sparse.slot(*moved) = Some(index);
+        }
+        Some(self.dense.swap_remove(index))
+    }
+
+    fn get(&self, id: usize) -> Option<&T> {
+        self.index(id).map(|index| &self.dense[index])
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        self.index(id).map(|index| &mut self.dense[index])
+    }
+
+    fn len(&self) -> usize {
+        self.dense.len()
+    }
+}
+
+impl<T> Default for ECSSparseSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Dense storage of a component.
+/// 
+/// Values are kept in a table indexed directly by entity id, for
+/// components most entities have. Iteration follows id order.
+/// 
+/// Inserting an id above [`ECS_MAX_ENTITY_ID`] panics.
+pub struct ECSDenseStorage<T> {
+    values: ECSPagedTable<T>,
+    len: usize
+}
+
+impl<T> ECSDenseStorage<T> {
+    /// Creates an empty dense storage
+    pub fn new() -> Self {
+        ECSDenseStorage {
+            values: ECSPagedTable::new(),
+            len: 0
+        }
+    }
+
+    /// Iterate over the ids and values in id order
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.values.iter()
+    }
+
+    /// Iterate over the ids and mutable values in id order
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (usize, &mut T)> {
+        self.values.iter_mut()
+    }
+}
+
+impl<T> ECSComponentStorage<T> for ECSDenseStorage<T> {
+    fn with_capacity(capacity: usize) -> Self {
+        let mut values = ECSPagedTable::new();
+        values.reserve_ids(capacity);
+        ECSDenseStorage {
+            values,
+            len: 0
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        // Ids are reused, so they are expected to stay below the capacity
+        self.values.reserve_ids(self.len.saturating_add(additional));
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+    }
+
+    fn insert(&mut self, id: usize, value: T) -> Option<T> {
+        let replaced = self.values.slot(id).replace(value);
+        if replaced.is_none() {
+            self.len += 1;
+        }
+        replaced
+    }
+
+    fn remove(&mut self, id: usize) -> Option<T> {
+        let removed = self.values.take(id);
+        if removed.is_some() {
+            self.len -= 1;
+        }
+        removed
+    }
+
+    fn get(&self, id: usize) -> Option<&T> {
+        self.values.get(id)
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        self.values.get_mut(id)
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> Default for ECSDenseStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Map storage of a component, for components few entities have
+impl<T> ECSComponentStorage<T> for std::collections::HashMap<usize, T> {
+    fn with_capacity(capacity: usize) -> Self {
+        std::collections::HashMap::with_capacity(capacity)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        std::collections::HashMap::reserve(self, additional);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        std::collections::HashMap::shrink_to_fit(self);
+    }
+
+    fn insert(&mut self, id: usize, value: T) -> Option<T> {
+        std::collections::HashMap::insert(self, id, value)
+    }
+
+    fn remove(&mut self, id: usize) -> Option<T> {
+        std::collections::HashMap::remove(self, &id)
+    }
+
+    fn get(&self, id: usize) -> Option<&T> {
+        std::collections::HashMap::get(self, &id)
+    }
+
+    fn get_mut(&mut self, id: usize) -> Option<&mut T> {
+        std::collections::HashMap::get_mut(self, &id)
+    }
+
+    fn len(&self) -> usize {
+        std::collections::HashMap::len(self)
+    }
+}
+
+/// Component storage behind a `RwLock`, used by locked archetypes
+/// shared between threads.
+/// 
+/// Values are kept with the generation of the entity they belong to,
+/// so an Id of a deleted entity never reaches the value of the entity
+/// its id was reused for. Every method takes `&self` and holds the
+/// lock only while it runs or while the returned guard lives. A lock
+/// poisoned by a panicking writer is recovered.
+pub struct ECSLockedStorage<T> {
+    values: std::sync::RwLock<ECSSparseSet<(usize, T)>>
+}
+
+impl<T> ECSLockedStorage<T> {
+    /// Creates an empty locked storage
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Creates an empty locked storage with room for ids below capacity
+    pub fn with_capacity(capacity: usize) -> Self {
+        ECSLockedStorage {
+            values: std::sync::RwLock::new(ECSComponentStorage::with_capacity(capacity))
+        }
+    }
+
+    /// Inserts the value of id and generation, returning the value
+    /// it replaced, whatever its generation
+    pub fn insert(&self, id: usize, generation: usize, value: T) -> Option<T> {
+        ECSComponentStorage::insert(&mut *self.write_all(), id, (generation, value))
+            .map(|(_, value)| value)
+    }
+
+    /// Removes the value of id if it is of generation
+    pub fn remove(&self, id: usize, generation: usize) -> Option<T> {
+        let mut values = self.write_all();
+        match ECSComponentStorage::get(&*values, id) {
+            Some((current, _)) if *current == generation => {
+                ECSComponentStorage::remove(&mut *values, id).map(|(_, value)| value)
+            },
+            _ => None
+        }
+    }
+
+    /// Checks if there is a value of id and generation
+    pub fn contains(&self, id: usize, generation: usize) -> bool {
+        Self::index(&self.read_all(), id, generation).is_some()
+    }
+
+    /// Gets the number of values
+    pub fn len(&self) -> usize {
+        ECSComponentStorage::len(&*self.read_all())
+    }
+
+    /// Checks if there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Locks the storage for reading and gets the value of id and
+    /// generation, blocking while it is locked for writing
+    pub fn read(&self, id: usize, generation: usize) -> Option<ECSLockedReadGuard<'_, T>> {
+        let values = self.read_all();
+        let index = Self::index(&values, id, generation)?;
+        Some(ECSLockedReadGuard { values, index })
+    }
+
+    /// Locks the storage for writing and gets the value of id and
+    /// generation, blocking while it is locked by readers or another writer
+    pub fn write(&self, id: usize, generation: usize) -> Option<ECSLockedWriteGuard<'_, T>> {
+        let values = self.write_all();
+        let index = Self::index(&values, id, generation)?;
+        Some(ECSLockedWriteGuard { values, index })
+    }
+
+    /// Gets the mutable value of id and generation without locking
+    pub fn get_mut(&mut self, id: usize, generation: usize) -> Option<&mut T> {
+        let values = self.values.get_mut().unwrap_or_else(std::sync::PoisonError::into_inner);
+        match ECSComponentStorage::get_mut(values, id) {
+            Some((current, value)) if *current == generation => Some(value),
+            _ => None
+        }
+    }
+
+    fn index(values: &ECSSparseSet<(usize, T)>, id: usize, generation: usize) -> Option<usize> {
+        values.index(id).filter(|index| values.values()[*index].0 == generation)
+    }
+
+    fn read_all(&self) -> std::sync::RwLockReadGuard<'_, ECSSparseSet<(usize, T)>> {
+        self.values.read().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn write_all(&self) -> std::sync::RwLockWriteGuard<'_, ECSSparseSet<(usize, T)>> {
+        self.values.write().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl<T> Default for ECSLockedStorage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read guard of a value in a [`ECSLockedStorage`], keeping the
+/// storage locked for reading while it lives
+pub struct ECSLockedReadGuard<'a, T> {
+    values: std::sync::RwLockReadGuard<'a, ECSSparseSet<(usize, T)>>,
+    index: usize
+}
+
+impl<T> std::ops::Deref for ECSLockedReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.values.values()[self.index].1
+    }
+}
+
+/// Write guard of a value in a [`ECSLockedStorage`], keeping the
+/// storage locked for writing while it lives
+pub struct ECSLockedWriteGuard<'a, T> {
+    values: std::sync::RwLockWriteGuard<'a, ECSSparseSet<(usize, T)>>,
+    index: usize
+}
+
+impl<T> std::ops::Deref for ECSLockedWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.values.values()[self.index].1
+    }
+}
+
+impl<T> std::ops::DerefMut for ECSLockedWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.values.values_mut()[self.index].1
+    }
+}
+
+thread_local! {
+    static ECS_ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Global allocator that counts the allocations of each thread,
+/// used by [`ECSCountingAllocator::assert_no_alloc`].
+/// 
+/// Install it in the binary or test that checks for allocations:
+/// ```
+/// #[global_allocator]
+/// static ALLOCATOR: qoecs::ECSCountingAllocator = qoecs::ECSCountingAllocator;
+/// ```
+pub struct ECSCountingAllocator;
+
+impl ECSCountingAllocator {
+    /// Gets the number of allocations made by the current thread
+    pub fn allocations() -> usize {
+        ECS_ALLOCATIONS.with(|allocations| allocations.get())
+    }
+
+    /// Runs `f`, panicking in debug builds if it allocated.
+    /// 
+    /// Only allocations of the current thread are counted, and only
+    /// when `ECSCountingAllocator` is the global allocator.
+    pub fn assert_no_alloc<R>(f: impl FnOnce() -> R) -> R {
+        let before = Self::allocations();
+        let result = f();
+        let allocations = Self::allocations() - before;
+        debug_assert!(allocations == 0, "Allocated {allocations} times.");
+        result
+    }
+
+    fn count() {
+        // The counter may already be destroyed while the thread exits
+        let _ = ECS_ALLOCATIONS.try_with(|allocations| allocations.set(allocations.get() + 1));
+    }
+}
+
+unsafe impl std::alloc::GlobalAlloc for ECSCountingAllocator {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        Self::count();
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: std::alloc::Layout) -> *mut u8 {
+        Self::count();
+        std::alloc::System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        Self::count();
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+}
+
+/// Calls the lifecycle hooks named `$hook` out of a list
+/// of component hooks.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_component_hook {
+    ($hook:ident, $id:expr, $value:expr;) => {};
+    (on_add, $id:expr, $value:expr; on_add: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $hook_fn($id, $value);
+        $crate::__create_ecs_component_hook!(on_add, $id, $value; $($($rest)*)?);
+    };
+    (on_replace, $id:expr, $value:expr; on_replace: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $hook_fn($id, $value);
+        $crate::__create_ecs_component_hook!(on_replace, $id, $value; $($($rest)*)?);
+    };
+    (on_remove, $id:expr, $value:expr; on_remove: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $hook_fn($id, $value);
+        $crate::__create_ecs_component_hook!(on_remove, $id, $value; $($($rest)*)?);
+    };
+    ($hook:ident, $id:expr, $value:expr; on_add: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $crate::__create_ecs_component_hook!($hook, $id, $value; $($($rest)*)?);
+    };
+    ($hook:ident, $id:expr, $value:expr; on_replace: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $crate::__create_ecs_component_hook!($hook, $id, $value; $($($rest)*)?);
+    };
+    ($hook:ident, $id:expr, $value:expr; on_remove: $hook_fn:path $(, $($rest:tt)*)?) => {
+        $crate::__create_ecs_component_hook!($hook, $id, $value; $($($rest)*)?);
+    };
+    ($hook:ident, $id:expr, $value:expr; $other:ident: $hook_fn:path $(, $($rest:tt)*)?) => {
+        compile_error!(concat!("Unknown component hook `", stringify!($other), "`."));
+    };
+}
+
+/// Picks the initial value of a resource, falling back
+/// to the default expression.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_initial_value {
+    ({ $($default:tt)* } = $init:expr) => {
+        $init
+    };
+    ({ $($default:tt)* }) => {
+        $($default)*
+    };
+}
+
+/// Picks the map type of a Map Resource
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_map_type {
+    (<$key:ty, $value:ty>) => {
+        std::collections::HashMap<$key, $value>
+    };
+    (ordered <$key:ty, $value:ty>) => {
+        std::collections::BTreeMap<$key, $value>
+    };
+    ($kind:ident <$key:ty, $value:ty>) => {
+        compile_error!(concat!("Unknown Map Resource kind `", stringify!($kind), "`."))
+    };
+}
+
+/// Picks the storage type of a component from its storage hint
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_component_storage_type {
+    (<$comp:ty>) => {
+        $crate::ECSSparseSet<$comp>
+    };
+    (sparse <$comp:ty>) => {
+        $crate::ECSSparseSet<$comp>
+    };
+    (dense <$comp:ty>) => {
+        $crate::ECSDenseStorage<$comp>
+    };
+    (map <$comp:ty>) => {
+        std::collections::HashMap<usize, $comp>
+    };
+    ($kind:ident <$comp:ty>) => {
+        compile_error!(concat!("Unknown component storage `", stringify!($kind), "`."))
+    };
+}
+
+/// Picks the entry type of a Map Resource
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_map_entry_type {
+    ($lifetime:lifetime <$key:ty, $value:ty>) => {
+        std::collections::hash_map::Entry<$lifetime, $key, $value>
+    };
+    ($lifetime:lifetime ordered <$key:ty, $value:ty>) => {
+        std::collections::btree_map::Entry<$lifetime, $key, $value>
+    };
+    ($lifetime:lifetime $kind:ident <$key:ty, $value:ty>) => {
+        compile_error!(concat!("Unknown Map Resource kind `", stringify!($kind), "`."))
+    };
+}
+
+/// Expands the code only for archetypes with Required components,
+/// which are passed as `[required $($req_name)*]`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_if_required {
+    ([] $($code:tt)*) => {};
+    ([required $($req_name:ident)*] $($code:tt)*) => {
+        $($code)*
+    };
+}
+
+/// Creates the entity Id type named `$id_name` of an archetype.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_entity_id {
+    ($id_name:ident) => {
+        #[derive(Clone, Debug)]
+        pub struct $id_name {
+            id: usize,
+            generation: usize,
+            valid: bool
+        }
+
+        impl $id_name {
+            /// Creates a new entity Id
+            pub fn new(id: usize) -> Self {
+                $id_name {
+                    id,
+                    generation: 0,
+                    valid: true
+                }
+            }
+
+            /// Creates a new entity Id of generation
+            pub(super) fn with_generation(id: usize, generation: usize) -> Self {
+                $id_name {
+                    id,
+                    generation,
+                    valid: true
+                }
+            }
+
+            /// Creates a new entity Id in a invalid state
+            pub fn new_invalidated(id: usize) -> Self {
+                $id_name {
+                    id,
+                    generation: 0,
+                    valid: false
+                }
+            }
+
+            /// Returns Id valid state
+            pub fn is_valid(&self) -> bool {
+                self.valid
+            }
+
+            /// Invalidates entity Id
+            pub(super) fn invalidate(&mut self) {
+                self.valid = false;
+            }
+
+            /// Recovers Id to a valid state advancing generation
+            #[allow(clippy::assign_op_pattern)]
+            pub(super) fn revalidate(&mut self) {
+                self.valid = true;
+                self.generation = self.generation + 1;
+            }
+
+            pub fn get_id(&self) -> &usize {
+                &self.id
+            }
+
+            pub fn get_generation(&self) -> &usize {
+                &self.generation
+            }
+        }
+
+        impl PartialEq for $id_name {
+            /// Compares if two entities have Id and are from
+            /// the same generation
+            fn eq(&self, rhs: &Self) -> bool {
+                (self.id == rhs.id) && (self.generation == rhs.generation)
+            }
+        }
+
+        impl PartialOrd for $id_name {
+            /// Compares the Ids of two entities
+            fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.id.cmp(&rhs.id))
+            }
+        }
+
+        impl Eq for $id_name {}
+
+        impl Ord for $id_name {
+            /// Compares the Ids of two entities
+            fn cmp(&self, rhs: &Self) -> std::cmp::Ordering {
+                self.id.cmp(&rhs.id)
+            }
+        }
+    };
+}
+
+/// Keeps the items it is given only with the `system-stats` feature.
+#[cfg(feature = "system-stats")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_if_system_stats {
+    ($($code:tt)*) => {
+        $($code)*
+    };
+}
+
+/// Keeps the items it is given only with the `system-stats` feature.
+#[cfg(not(feature = "system-stats"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_if_system_stats {
+    ($($code:tt)*) => {};
+}
+
+/// Gets the schedule a stage of kind `$kind` runs in, and
+/// rejects unknown stage kinds.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_stage_schedule {
+    (Startup) => { $crate::ECSSchedule::Startup };
+    (Stage) => { $crate::ECSSchedule::Update };
+    (Exclusive) => { $crate::ECSSchedule::Update };
+    (Shutdown) => { $crate::ECSSchedule::Shutdown };
+    ($kind:ident) => {
+        compile_error!(concat!(
+            "Unknown stage kind `", stringify!($kind), "`, expected `Startup`, `Stage`, `Exclusive` or `Shutdown`."
+        ))
+    };
+}
+
+/// Gets the type of the local state of a system, `()` if it
+/// has none.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_system_local_type {
+    () => { () };
+    ($local_ty:ty) => { $local_ty };
+}
+
+/// Calls a system with `$arg`s, followed by its local state if
+/// it has one.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_call_system {
+    ($system_fn:path[$($arg:expr),*], $local:expr) => {
+        {
+            let _ = $local;
+            $system_fn($($arg),*)
+        }
+    };
+    ($system_fn:path[$($arg:expr),*], $local:expr, $local_ty:ty) => {
+        $system_fn($($arg,)* $local)
+    };
+}
+
+/// Keeps the code for the systems of a stage of kind `$kind`, the
+/// second block for `Exclusive` stages and the first one otherwise.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_stage_kind {
+    (Exclusive { $($shared:tt)* } { $($exclusive:tt)* }) => {
+        $($exclusive)*
+    };
+    ($kind:ident { $($shared:tt)* } { $($exclusive:tt)* }) => {
+        $($shared)*
+    };
+}
+
+/// Creates the `new` and `new_with` constructors, taking
+/// the Resources without initial value as parameters of `new_with`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __create_ecs_constructors {
+    (
+        $name:ident;
+        [$($param:ident: $param_ty:ty),*];
+        [[$resource_name:ident: $resource_ty:ty = $resource_init:expr] $($rest:tt)*];
+        $body:block
+    ) => {
+        $crate::__create_ecs_constructors!($name; [$($param: $param_ty),*]; [$($rest)*]; $body);
+    };
+    (
+        $name:ident;
+        [$($param:ident: $param_ty:ty),*];
+        [[$resource_name:ident: $resource_ty:ty] $($rest:tt)*];
+        $body:block
+    ) => {
+        $crate::__create_ecs_constructors!(
+            $name;
+            [$($param: $param_ty,)* $resource_name: $resource_ty];
+            [$($rest)*];
+            $body
+        );
+    };
+    (
+        $name:ident;
+        [$($param:ident: $param_ty:ty),*];
+        [];
+        $body:block
+    ) => {
+        /// Create a new instance of ECS struct
+        /// 
+        /// Resources without initial value start as `Default::default()`.
+        // Higher-ranked bounds are only checked when `new` is called,
+        // so ECSs with non `Default` Resources can still use `new_with`.
+        fn new() -> $name where $(for<'a> $param_ty: Default),* {
+            $name::new_with($(<$param_ty>::default()),*)
+        }
+
+        /// Create a new instance of ECS struct with the values
+        /// of the Resources without initial value
+        fn new_with($($param: $param_ty),*) -> $name $body
+    };
+}
+
+#[macro_export]
+macro_rules! create_ecs {
+    (
+        // ECS Struct name
+        $name:ident,
+        // ECS Required resources
+        Resources(
+            $(
+                $resource_name:ident => $resource_ty:ty $(= $resource_init:expr)?
+            ),*
+        ),
+        // ECS Array resources
+        ArrayResources(
+            $(
+                $arr_resource_name:ident => [$arr_resource_ty:ty; $arr_resource_len:literal] $(= $arr_resource_init:expr)?
+            ),*
+        ),
+        // ECS Map resources
+        MapResources(
+            $(
+                $map_resource_name:ident => <$map_resource_key_type:ty, $map_resource_value_type:ty> $(@$map_resource_kind:ident)?
+            ),*
+        ),
+        // ECS Optional resources
+        OptionalResources(
+            $(
+                $opt_resource_name:ident => $opt_resource_ty:ty
+            ),*
+        ),
+        // ECS Vec resources
+        VecResources(
+            $(
+                $vec_resource_name:ident => $vec_resource_ty:ty
+            ),*
+        ),
+        // ECS Ring resources
+        RingResources(
+            $(
+                $ring_resource_name:ident => [$ring_resource_ty:ty; $ring_resource_len:literal]
+            ),*
+        ),
+        // ECS Locked resources
+        LockedResources(
+            $(
+                $lock_resource_name:ident => $lock_resource_ty:ty $(= $lock_resource_init:expr)?
+            ),*
+        ),
+        // ECS Events
+        Events(
+            $(
+                $event_name:ident => $event_ty:ty
+            ),*
+        ),
+        // ECS Entity Archtypes
+        Archtypes(
+            $(
+                Entity(
+                    $entity_name:ident,
+                    // ECS Entity archtype initial capacity
+                    $(capacity = $entity_capacity:expr,)?
+                    // ECS Entity archtype fixed capacity
+                    $(fixed_capacity = $entity_fixed_capacity:expr,)?
+                    // ECS Entity archtype Required components
+                    $(
+                        Required(
+                            $(
+                                $req_name:ident => $req_type:ty $({
+                                    // Component lifecycle hooks
+                                    $($req_hook:ident: $req_hook_fn:path),* $(,)?
+                                })?
+                            ),*
+                        ),
+                    )?
+                    // ECS Entity archtype Components
+                    Components(
+                        $(
+                            $comp_name:ident => $(@$comp_storage:ident)? $comp_type:ty $({
+                                // Component lifecycle hooks
+                                $($comp_hook:ident: $comp_hook_fn:path),* $(,)?
+                            })?
+                        ),*
+                    )
+                )
+            ),+
+        ),
+        // ECS Locked Entity Archtypes
+        LockedArchtypes(
+            $(
+                Entity(
+                    $locked_entity_name:ident,
+                    // ECS Locked Entity archtype initial capacity
+                    $(capacity = $locked_entity_capacity:expr,)?
+                    // ECS Locked Entity archtype Components
+                    Components(
+                        $(
+                            $locked_comp_name:ident => $locked_comp_type:ty
+                        ),*
+                    )
+                )
+            ),*
+        ),
+        // ECS Systems, grouped in stages run in order
+        Systems(
+            $(
+                // ECS stage kind, `Startup`, `Stage`, `Exclusive` or `Shutdown`
+                $stage_kind:ident(
+                    $(
+                        System(
+                            $system_name:ident => $system_fn:path
+                            // ECS System declared access
+                            $(, Reads($($system_read:ident),*))?
+                            $(, Writes($($system_write:ident),*))?
+                            // ECS System local state
+                            $(, Local($system_local:ty))?
+                        )
+                    ),*
+                )
+            ),*
+        )
+    ) => {
+        paste::paste! { 
+        mod id {
+            $(
+                $crate::__create_ecs_entity_id!([<$entity_name:camel EntityId>]);
+            )*
+            $(
+                $crate::__create_ecs_entity_id!([<$locked_entity_name:camel EntityId>]);
+            )*
+        }
+
+        mod entity {
+            $(
+                pub struct [<$entity_name:camel Entity>] {
+                    $($(pub $req_name: $req_type,)*)?
+                    $(pub $comp_name: Option<$comp_type>),*
+                }
+
+                pub struct [<$entity_name:camel EntityView>]<'a> {
+                    $($(pub $req_name: &'a $req_type,)*)?
+                    $(pub $comp_name: Option<&'a $comp_type>),*
+                }
+            )*
+            $(
+                pub struct [<$locked_entity_name:camel Entity>] {
+                    $(pub $locked_comp_name: Option<$locked_comp_type>),*
+                }
+            )*
+        }
+
+        mod resource {
+            // Resource marker types
+            $(pub struct [<$resource_name:camel>];)*
+            // Array Resource marker types
+            $(pub struct [<$arr_resource_name:camel>];)*
+            // Map Resource marker types
+            $(pub struct [<$map_resource_name:camel>];)*
+            // Optional Resource marker types
+            $(pub struct [<$opt_resource_name:camel>];)*
+            // Vec Resource marker types
+            $(pub struct [<$vec_resource_name:camel>];)*
+            // Ring Resource marker types
+            $(pub struct [<$ring_resource_name:camel>];)*
+        }
+
+        mod event {
+            // Event marker types
+            $(pub struct [<$event_name:camel>];)*
+        }
+
+        mod archetype {
+            // Archtype marker types
+            $(pub struct [<$entity_name:camel>];)+
+        }
+
+        mod system {
+            /// Resource parameter of a runtime system, `M` being a
+            /// marker type of `resource`
+            pub type Res<'w, M> = $crate::ECSRes<'w, super::$name, M>;
+
+            /// Query parameter of a runtime system, `M` being a
+            /// marker type of `archetype`
+            pub type Query<'w, M> = $crate::ECSQuery<'w, super::$name, M>;
+
+            /// Event reader parameter of a runtime system, `M` being
+            /// a marker type of `event`
+            pub type Reader<'w, M> = $crate::ECSReader<'w, super::$name, M>;
+        }
+
+        mod command {
+            /// Change recorded by Commands
+            pub(super) enum Command {
+                $([<WriteResource $resource_name:camel>]($resource_ty),)*
+                $(
+                    [<WriteResource $arr_resource_name:camel>]($arr_resource_ty, usize),
+                    [<ClearResource $arr_resource_name:camel>],
+                )*
+                $(
+                    [<WriteResource $map_resource_name:camel>]($map_resource_key_type, $map_resource_value_type),
+                    [<ClearResource $map_resource_name:camel>],
+                )*
+                $(
+                    [<WriteResource $opt_resource_name:camel>]($opt_resource_ty),
+                    [<ClearResource $opt_resource_name:camel>],
+                )*
+                $(
+                    [<PushResource $vec_resource_name:camel>]($vec_resource_ty),
+                    [<ClearResource $vec_resource_name:camel>],
+                )*
+                $(
+                    [<PushResource $ring_resource_name:camel>]($ring_resource_ty),
+                    [<ClearResource $ring_resource_name:camel>],
+                )*
+                $([<WriteResource $lock_resource_name:camel>]($lock_resource_ty),)*
+                $(
+                    [<Spawn $entity_name:camel>](
+                        super::id::[<$entity_name:camel EntityId>],
+                        super::entity::[<$entity_name:camel Entity>]
+                    ),
+                    [<Delete $entity_name:camel>](super::id::[<$entity_name:camel EntityId>]),
+                    $($(
+                        [<Add $req_name:camel To $entity_name:camel>](
+                            super::id::[<$entity_name:camel EntityId>],
+                            $req_type
+                        ),
+                    )*)?
+                    $(
+                        [<Add $comp_name:camel To $entity_name:camel>](
+                            super::id::[<$entity_name:camel EntityId>],
+                            $comp_type
+                        ),
+                        [<Remove $comp_name:camel From $entity_name:camel>](
+                            super::id::[<$entity_name:camel EntityId>]
+                        ),
+                    )*
+                )+
+            }
+
+            /// Buffer of structural changes and resource writes
+            /// to be applied to the ECS at a later point
+            pub struct Commands {
+                pub(super) queue: Vec<Command>,
+                $(pub(super) $entity_name: $crate::ECSIdAllocator,)+
+            }
+
+            impl Commands {
+                // Recording Resource commands
+                $(
+                /// Records a write to Resource
+                pub fn [<write_resource_ $resource_name>](&mut self, $resource_name: $resource_ty) {
+                    self.queue.push(Command::[<WriteResource $resource_name:camel>]($resource_name));
+                }
+                )*
+
+                // Recording Array Resource commands
+                $(
+                /// Records a write to Array Resource at index
+                pub fn [<write_resource_ $arr_resource_name>](
+                    &mut self,
+                    $arr_resource_name: $arr_resource_ty,
+                    index: usize
+                ) {
+                    self.queue.push(Command::[<WriteResource $arr_resource_name:camel>]($arr_resource_name, index));
+                }
+
+                /// Records a clear of Array Resource
+                pub fn [<clear_resource_ $arr_resource_name>](&mut self) {
+                    self.queue.push(Command::[<ClearResource $arr_resource_name:camel>]);
+                }
+                )*
+
+                // Recording Map Resource commands
+                $(
+                /// Records a write to Map Resource for key
+                pub fn [<write_resource_ $map_resource_name>](
+                    &mut self,
+                    [<$map_resource_name _key>]: $map_resource_key_type,
+                    [<$map_resource_name _value>]: $map_resource_value_type
+                ) {
+                    self.queue.push(Command::[<WriteResource $map_resource_name:camel>](
+                        [<$map_resource_name _key>],
+                        [<$map_resource_name _value>]
+                    ));
+                }
+
+                /// Records a clear of Map Resource
+                pub fn [<clear_resource_ $map_resource_name>](&mut self) {
+                    self.queue.push(Command::[<ClearResource $map_resource_name:camel>]);
+                }
+                )*
+
+                // Recording Optional Resource commands
+                $(
+                /// Records a write to Optional Resource
+                pub fn [<write_resource_ $opt_resource_name>](&mut self, $opt_resource_name: $opt_resource_ty) {
+                    self.queue.push(Command::[<WriteResource $opt_resource_name:camel>]($opt_resource_name));
+                }
+
+                /// Records a clear of Optional Resource
+                pub fn [<clear_resource_ $opt_resource_name>](&mut self) {
+                    self.queue.push(Command::[<ClearResource $opt_resource_name:camel>]);
+                }
+                )*
+
+                // Recording Vec Resource commands
+                $(
+                /// Records a push to Vec Resource
+                pub fn [<push_resource_ $vec_resource_name>](&mut self, $vec_resource_name: $vec_resource_ty) {
+                    self.queue.push(Command::[<PushResource $vec_resource_name:camel>]($vec_resource_name));
+                }
+
+                /// Records a clear of Vec Resource
+                pub fn [<clear_resource_ $vec_resource_name>](&mut self) {
+                    self.queue.push(Command::[<ClearResource $vec_resource_name:camel>]);
+                }
+                )*
+
+                // Recording Ring Resource commands
+                $(
+                /// Records a push to Ring Resource
+                pub fn [<push_resource_ $ring_resource_name>](&mut self, $ring_resource_name: $ring_resource_ty) {
+                    self.queue.push(Command::[<PushResource $ring_resource_name:camel>]($ring_resource_name));
+                }
+
+                /// Records a clear of Ring Resource
+                pub fn [<clear_resource_ $ring_resource_name>](&mut self) {
+                    self.queue.push(Command::[<ClearResource $ring_resource_name:camel>]);
+                }
+                )*
+
+                // Recording Locked Resource commands
+                $(
+                /// Records a write to Locked Resource
+                pub fn [<write_resource_ $lock_resource_name>](&mut self, $lock_resource_name: $lock_resource_ty) {
+                    self.queue.push(Command::[<WriteResource $lock_resource_name:camel>]($lock_resource_name));
+                }
+                )*
+
+                // Recording Entity Archtype commands
+                $(
+                    /// Reserves an Id and records the creation of an
+                    /// entity with it.
+                    /// 
+                    /// The Id can be used by other commands before they
+                    /// are applied. The Id is released if Commands is dropped
+                    /// before it is applied.
+                    pub fn [<spawn_ $entity_name>](
+                        &mut self,
+                        $entity_name: super::entity::[<$entity_name:camel Entity>]
+                    ) -> super::id::[<$entity_name:camel EntityId>] {
+                        let (id, generation) = self.$entity_name.reserve();
+                        let id = super::id::[<$entity_name:camel EntityId>]::with_generation(id, generation);
+                        self.queue.push(Command::[<Spawn $entity_name:camel>](id.clone(), $entity_name));
+                        id
+                    }
+
+                    /// Records the deletion of entity of Id
+                    pub fn [<delete_ $entity_name>](
+                        &mut self,
+                        [<$entity_name _id>]: &super::id::[<$entity_name:camel EntityId>]
+                    ) {
+                        self.queue.push(Command::[<Delete $entity_name:camel>]([<$entity_name _id>].clone()));
+                    }
+
+                    $($(
+                        /// Records the replacement of a required component of a Entity
+                        pub fn [<add_ $req_name _to_ $entity_name>](
+                            &mut self,
+                            [<$entity_name _id>]: &super::id::[<$entity_name:camel EntityId>],
+                            $req_name: $req_type
+                        ) {
+                            self.queue.push(Command::[<Add $req_name:camel To $entity_name:camel>](
+                                [<$entity_name _id>].clone(),
+                                $req_name
+                            ));
+                        }
+                    )*)?
+
+                    $(
+                        /// Records the addition of a component to a Entity
+                        pub fn [<add_ $comp_name _to_ $entity_name>](
+                            &mut self,
+                            [<$entity_name _id>]: &super::id::[<$entity_name:camel EntityId>],
+                            $comp_name: $comp_type
+                        ) {
+                            self.queue.push(Command::[<Add $comp_name:camel To $entity_name:camel>](
+                                [<$entity_name _id>].clone(),
+                                $comp_name
+                            ));
+                        }
+
+                        /// Records the removal of a component from a Entity
+                        pub fn [<remove_ $comp_name _from_ $entity_name>](
+                            &mut self,
+                            [<$entity_name _id>]: &super::id::[<$entity_name:camel EntityId>]
+                        ) {
+                            self.queue.push(Command::[<Remove $comp_name:camel From $entity_name:camel>](
+                                [<$entity_name _id>].clone()
+                            ));
+                        }
+                    )*
+                )+
+
+                /// Returns the number of recorded commands
+                pub fn len(&self) -> usize {
+                    self.queue.len()
+                }
+
+                /// Returns `true` if no commands were recorded
+                pub fn is_empty(&self) -> bool {
+                    self.queue.is_empty()
+                }
+            }
+
+            impl Drop for Commands {
+                /// Releases the Ids reserved by spawns that were not applied
+                fn drop(&mut self) {
+                    for command in &self.queue {
+                        match command {
+                            $(
+                                Command::[<Spawn $entity_name:camel>]([<$entity_name _id>], _) => {
+                                    self.$entity_name.release(
+                                        *[<$entity_name _id>].get_id(),
+                                        *[<$entity_name _id>].get_generation()
+                                    );
+                                },
+                            )+
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        struct $name {
+            // Tick of the last change to a resource
+            change_tick: std::sync::atomic::AtomicUsize,
+            // Required resource properties
+            // Resources are `None` while taken by a resource scope
+            $(
+                [<resource_ $resource_name>]: Option<$resource_ty>,
+                [<resource_ $resource_name _changed>]: usize,
+            )*
+            // Array resource properties
+            $(
+                [<resource_ $arr_resource_name>]: [$arr_resource_ty; $arr_resource_len],
+                [<resource_ $arr_resource_name _changed>]: [usize; $arr_resource_len],
+            )*
+            // Map resource properties
+            $(
+                [<resource_ $map_resource_name>]: $crate::__create_ecs_map_type!(
+                    $($map_resource_kind)? <$map_resource_key_type, $map_resource_value_type>
+                ),
+                [<resource_ $map_resource_name _changed>]: usize,
+                [<resource_ $map_resource_name _key_changed>]: $crate::__create_ecs_map_type!(
+                    $($map_resource_kind)? <$map_resource_key_type, usize>
+                ),
+                [<resource_ $map_resource_name _removed>]: usize,
+            )*
+            // Optional resource properties
+            $(
+                [<resource_ $opt_resource_name>]: Option<$opt_resource_ty>,
+                [<resource_ $opt_resource_name _changed>]: usize,
+            )*
+            // Vec resource properties
+            $(
+                [<resource_ $vec_resource_name>]: Vec<$vec_resource_ty>,
+                [<resource_ $vec_resource_name _changed>]: usize,
+            )*
+            // Ring resource properties
+            $(
+                [<resource_ $ring_resource_name>]: std::collections::VecDeque<$ring_resource_ty>,
+                [<resource_ $ring_resource_name _changed>]: usize,
+            )*
+            // Locked resource properties
+            $(
+                [<resource_ $lock_resource_name>]: std::sync::RwLock<$lock_resource_ty>,
+                [<resource_ $lock_resource_name _changed>]: std::sync::atomic::AtomicUsize,
+            )*
+            // Event queue properties
+            $([<event_ $event_name>]: $crate::ECSEvents<$event_ty>,)*
+            // Commands queued by observers
+            pending_commands: Vec<command::Command>,
+            // Whether the Startup systems ran
+            systems_started: bool,
+            // Number of `run_systems` calls
+            system_tick: usize,
+            // Error policies of the Startup, Update and Shutdown schedules
+            system_error_policies: [$crate::ECSErrorPolicy; 3],
+            // System enabled state properties
+            $($(
+                [<system_ $system_name _enabled>]: bool,
+            )*)*
+            // System statistics properties
+            $($(
+                [<system_ $system_name _stats>]: $crate::ECSSystemStats,
+            )*)*
+            // Duration systems warn about taking longer than
+            system_budget: Option<std::time::Duration>,
+            // Systems added with `add_system`
+            runtime_systems: Vec<$crate::ECSRuntimeSystem<$name>>,
+            // System local state properties
+            $($(
+                [<system_ $system_name _local>]: $crate::__create_ecs_system_local_type!($($system_local)?),
+            )*)*
+            // Archtype member properties
+            $(
+                // Entity Ids member property, indexed by id
+                $entity_name: $crate::ECSDenseStorage<id::[<$entity_name:camel EntityId>]>,
+                // Entity Id allocator member property
+                [<$entity_name _id_allocator>]: $crate::ECSIdAllocator,
+                // Entity observers member property
+                [<$entity_name _observers>]: Vec<(
+                    $crate::ECSEntityEvent,
+                    Box<dyn FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync>
+                )>,
+                // Entity rows of required components member property,
+                // only for archetypes with Required components
+                $(
+                    $(#[cfg_attr(any(), doc = stringify!($req_name))])*
+                    [<$entity_name _rows>]: $crate::ECSSparseSet<()>,
+                )?
+                // Entity required components columns member properties
+                $($(
+                    [<$entity_name _ $req_name>]: Vec<$req_type>,
+                )*)?
+                // Entity required components observers member properties
+                $($(
+                    [<$entity_name _ $req_name _observers>]: Vec<(
+                        $crate::ECSComponentEvent,
+                        Box<dyn FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync>
+                    )>,
+                )*)?
+                // Entity components member properties
+                $(
+                    [<$entity_name _ $comp_name>]: $crate::__create_ecs_component_storage_type!(
+                        $($comp_storage)? <$comp_type>
+                    ),
+                )*
+                // Entity components observers member properties
+                $(
+                    [<$entity_name _ $comp_name _observers>]: Vec<(
+                        $crate::ECSComponentEvent,
+                        Box<dyn FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync>
+                    )>,
+                )*
+            )+
+            // Locked archtype member properties
+            $(
+                // Locked Entity generations member property, indexed by id
+                $locked_entity_name: $crate::ECSLockedStorage<()>,
+                // Locked Entity Id allocator member property
+                [<$locked_entity_name _id_allocator>]: $crate::ECSIdAllocator,
+                // Locked Entity components member properties
+                $(
+                    [<$locked_entity_name _ $locked_comp_name>]: $crate::ECSLockedStorage<$locked_comp_type>,
+                )*
+            )*
+        }
+
+        impl $name {
+            $crate::__create_ecs_constructors!(
+                $name;
+                [];
+                [
+                    $([$resource_name: $resource_ty $(= $resource_init)?])*
+                    $([$lock_resource_name: $lock_resource_ty $(= $lock_resource_init)?])*
+                ];
+                {
+                $name {
+                    // No resource changed yet
+                    change_tick: std::sync::atomic::AtomicUsize::new(0),
+                    // Required resources initial value or parameter initialization
+                    $(
+                        [<resource_ $resource_name>]: Some($crate::__create_ecs_initial_value!(
+                            { $resource_name } $(= $resource_init)?
+                        )),
+                        [<resource_ $resource_name _changed>]: 0,
+                    )*
+                    // Array resources initial value or default list initialization
+                    $(
+                        [<resource_ $arr_resource_name>]: $crate::__create_ecs_initial_value!(
+                            { std::array::from_fn::<$arr_resource_ty, $arr_resource_len, _>(|_| Default::default()) } $(= $arr_resource_init)?
+                        ),
+                        [<resource_ $arr_resource_name _changed>]: [0; $arr_resource_len],
+                    )*
+                    // Map resources empty map initialization
+                    $(
+                        [<resource_ $map_resource_name>]: Default::default(),
+                        [<resource_ $map_resource_name _changed>]: 0,
+                        [<resource_ $map_resource_name _key_changed>]: Default::default(),
+                        [<resource_ $map_resource_name _removed>]: 0,
+                    )*
+                    // Optional resources None initialization
+                    $(
+                        [<resource_ $opt_resource_name>]: None,
+                        [<resource_ $opt_resource_name _changed>]: 0,
+                    )*
+                    // Vec resources empty list initialization
+                    $(
+                        [<resource_ $vec_resource_name>]: vec![],
+                        [<resource_ $vec_resource_name _changed>]: 0,
+                    )*
+                    // Ring resources empty list initialization
+                    $(
+                        [<resource_ $ring_resource_name>]: std::collections::VecDeque::with_capacity($ring_resource_len),
+                        [<resource_ $ring_resource_name _changed>]: 0,
+                    )*
+                    // Locked resources initial value or parameter initialization
+                    $(
+                        [<resource_ $lock_resource_name>]: std::sync::RwLock::new(
+                            $crate::__create_ecs_initial_value!(
+                                { $lock_resource_name } $(= $lock_resource_init)?
+                            )
+                        ),
+                        [<resource_ $lock_resource_name _changed>]: std::sync::atomic::AtomicUsize::new(0),
+                    )*
+                    // Event queues empty initialization
+                    $([<event_ $event_name>]: $crate::ECSEvents::new(),)*
+                    // Pending commands empty initialization
+                    pending_commands: vec![],
+                    // Startup systems initialization
+                    systems_started: false,
+                    // System tick initialization
+                    system_tick: 0,
+                    // System error policies initialization
+                    system_error_policies: [$crate::ECSErrorPolicy::Stop; 3],
+                    // Systems enabled initialization
+                    $($(
+                        [<system_ $system_name _enabled>]: true,
+                    )*)*
+                    // Systems empty statistics initialization
+                    $($(
+                        [<system_ $system_name _stats>]: $crate::ECSSystemStats::default(),
+                    )*)*
+                    // System budget initialization
+                    system_budget: None,
+                    // Runtime systems empty initialization
+                    runtime_systems: vec![],
+                    // Systems local state initialization
+                    $($(
+                        [<system_ $system_name _local>]: Default::default(),
+                    )*)*
+                    // Archtype storage initialization
+                    $(
+                        // Entity empty Ids initialization
+                        $entity_name: $crate::ECSComponentStorage::with_capacity(
+                            Self::[<$entity_name:upper _CAPACITY>]
+                        ),
+                        // Entity Id allocator initialization
+                        [<$entity_name _id_allocator>]: match Self::[<$entity_name:upper _FIXED_CAPACITY>] {
+                            Some(capacity) => $crate::ECSIdAllocator::fixed(capacity),
+                            None => $crate::ECSIdAllocator::with_capacity(Self::[<$entity_name:upper _CAPACITY>])
+                        },
+                        // Entity observers empty list initialization
+                        [<$entity_name _observers>]: vec![],
+                        // Entity rows empty initialization
+                        $(
+                            $(#[cfg_attr(any(), doc = stringify!($req_name))])*
+                            [<$entity_name _rows>]: $crate::ECSComponentStorage::with_capacity(
+                                Self::[<$entity_name:upper _CAPACITY>]
+                            ),
+                        )?
+                        // Entity required components empty columns initialization
+                        $($(
+                            [<$entity_name _ $req_name>]: Vec::with_capacity(
+                                Self::[<$entity_name:upper _CAPACITY>]
+                            ),
+                        )*)?
+                        // Entity required components observers empty list initialization
+                        $($(
+                            [<$entity_name _ $req_name _observers>]: vec![],
+                        )*)?
+                        // Entity components empty storage initialization
+                        $(
+                            [<$entity_name _ $comp_name>]: $crate::ECSComponentStorage::with_capacity(
+                                Self::[<$entity_name:upper _CAPACITY>]
+                            ),
+                        )*
+                        // Entity components observers empty list initialization
+                        $(
+                            [<$entity_name _ $comp_name _observers>]: vec![],
+                        )*
+                    )+
+                    // Locked archtype storage initialization
+                    $(
+                        // Locked Entity empty generations initialization
+                        $locked_entity_name: $crate::ECSLockedStorage::with_capacity(
+                            Self::[<$locked_entity_name:upper _CAPACITY>]
+                        ),
+                        // Locked Entity Id allocator initialization
+                        [<$locked_entity_name _id_allocator>]: $crate::ECSIdAllocator::with_capacity(
+                            Self::[<$locked_entity_name:upper _CAPACITY>]
+                        ),
+                        // Locked Entity components empty storage initialization
+                        $(
+                            [<$locked_entity_name _ $locked_comp_name>]: $crate::ECSLockedStorage::with_capacity(
+                                Self::[<$locked_entity_name:upper _CAPACITY>]
+                            ),
+                        )*
+                    )*
+                }
+                }
+            );
+
+            /// Get the tick of the last change to a resource.
+            /// 
+            /// Every change to a resource advances the tick, so a change
+            /// made after reading it makes `resource_<name>_changed_since`
+            /// return `true` for it.
+            fn change_tick(&self) -> usize {
+                self.change_tick.load(std::sync::atomic::Ordering::Acquire)
+            }
+
+            /// Advances the tick of the last change to a resource
+            fn _next_change_tick(&self) -> usize {
+                self.change_tick.fetch_add(1, std::sync::atomic::Ordering::AcqRel) + 1
+            }
+
+            // Creating Resource methods
+            $(
+            /// Message of the panic when accessing Resource while taken
+            const [<RESOURCE_ $resource_name:upper _TAKEN>]: &'static str = concat!(
+                "Resource `", stringify!($resource_name), "` is taken by `resource_scope_",
+                stringify!($resource_name), "`."
+            );
+
+            /// Write a value to Resource
+            /// 
+            /// # Panics
+            /// Panics if Resource is taken by its resource scope.
+            fn [<write_resource_ $resource_name>](&mut self, $resource_name: $resource_ty) {
+                assert!(
+                    self.[<resource_ $resource_name>].is_some(),
+                    "{}",
+                    Self::[<RESOURCE_ $resource_name:upper _TAKEN>]
+                );
+                self.[<resource_ $resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $resource_name>] = Some($resource_name);
+            }
+
+            /// Get the value of Resource
+            /// 
+            /// # Panics
+            /// Panics if Resource is taken by its resource scope.
+            fn [<get_resource_ $resource_name>](&self) -> &$resource_ty {
+                self.[<resource_ $resource_name>]
+                    .as_ref()
+                    .expect(Self::[<RESOURCE_ $resource_name:upper _TAKEN>])
+            }
+
+            /// Get the mutable value of Resource
+            /// 
+            /// # Panics
+            /// Panics if Resource is taken by its resource scope.
+            fn [<get_resource_ $resource_name _mut>](&mut self) -> &mut $resource_ty {
+                self.[<resource_ $resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $resource_name>]
+                    .as_mut()
+                    .expect(Self::[<RESOURCE_ $resource_name:upper _TAKEN>])
+            }
+
+            /// Takes Resource out of the ECS for the duration of `scope`,
+            /// giving it mutable access to both.
+            /// 
+            /// Resource is put back even if `scope` panics.
+            /// 
+            /// # Panics
+            /// Panics if Resource is already taken, and `scope` panics if
+            /// it accesses Resource through the ECS.
+            fn [<resource_scope_ $resource_name>]<R>(
+                &mut self,
+                scope: impl FnOnce(&mut Self, &mut $resource_ty) -> R
+            ) -> R {
+                let mut $resource_name = self.[<resource_ $resource_name>]
+                    .take()
+                    .expect(Self::[<RESOURCE_ $resource_name:upper _TAKEN>]);
+                let result = std::panic::catch_unwind(
+                    std::panic::AssertUnwindSafe(|| scope(self, &mut $resource_name))
+                );
+                self.[<resource_ $resource_name>] = Some($resource_name);
+                self.[<resource_ $resource_name _changed>] = self._next_change_tick();
+                match result {
+                    Ok(result) => result,
+                    Err(panic) => std::panic::resume_unwind(panic)
+                }
+            }
+
+            /// Update the value of Resource in place
+            fn [<update_resource_ $resource_name>](&mut self, update: impl FnOnce(&mut $resource_ty)) {
+                update(self.[<get_resource_ $resource_name _mut>]());
+            }
+
+            /// Checks if Resource changed after tick
+            fn [<resource_ $resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $resource_name _changed>] > tick
+            }
+            )*
+
+            // Creating Array Resource methods
+            $(
+            /// Write a value to Array Resource at index
+            fn [<write_resource_ $arr_resource_name>](
+                &mut self,
+                $arr_resource_name: $arr_resource_ty,
+                index: usize
+            ) -> Result<(), $crate::ECSError> {
+                if index < self.[<resource_ $arr_resource_name>].len() {
+                    self.[<resource_ $arr_resource_name _changed>][index] = self._next_change_tick();
+                    self.[<resource_ $arr_resource_name>][index] = $arr_resource_name;
+                    Ok(())
+                } else {
+                    Err($crate::ECSError::ArrayResourceWriteOutOfBoundsError)
+                }
+            }
+
+            /// Clear all values of Array Resource
+            fn [<clear_resource_ $arr_resource_name>](&mut self) {
+                self.[<resource_ $arr_resource_name _changed>] = [self._next_change_tick(); $arr_resource_len];
+                self.[<resource_ $arr_resource_name>] = std::array::from_fn(|_| Default::default());
+            }
+
+            /// Get the value of Array Resource at index
+            fn [<get_resource_ $arr_resource_name>](
+                &self,
+                index: usize
+            ) -> Result<&$arr_resource_ty, $crate::ECSError> {
+                if index < self.[<resource_ $arr_resource_name>].len() {
+                    let v = &self.[<resource_ $arr_resource_name>][index];
+                    Ok(&v)
+                } else {
+                    Err($crate::ECSError::ArrayResourceWriteOutOfBoundsError)
+                }
+            }
+
+            /// Get the mutable value of Array Resource at index
+            fn [<get_resource_ $arr_resource_name _mut>](
+                &mut self,
+                index: usize
+            ) -> Result<&mut $arr_resource_ty, $crate::ECSError> {
+                if index < self.[<resource_ $arr_resource_name>].len() {
+                    self.[<resource_ $arr_resource_name _changed>][index] = self._next_change_tick();
+                    Ok(&mut self.[<resource_ $arr_resource_name>][index])
+                } else {
+                    Err($crate::ECSError::ArrayResourceWriteOutOfBoundsError)
+                }
+            }
+
+            /// Update the value of Array Resource at index in place
+            fn [<update_resource_ $arr_resource_name>](
+                &mut self,
+                index: usize,
+                update: impl FnOnce(&mut $arr_resource_ty)
+            ) -> Result<(), $crate::ECSError> {
+                update(self.[<get_resource_ $arr_resource_name _mut>](index)?);
+                Ok(())
+            }
+
+            /// Swap the values of Array Resource at two indices
+            fn [<swap_resource_ $arr_resource_name>](
+                &mut self,
+                index: usize,
+                other_index: usize
+            ) -> Result<(), $crate::ECSError> {
+                let len = self.[<resource_ $arr_resource_name>].len();
+                if index < len && other_index < len {
+                    let tick = self._next_change_tick();
+                    self.[<resource_ $arr_resource_name _changed>][index] = tick;
+                    self.[<resource_ $arr_resource_name _changed>][other_index] = tick;
+                    self.[<resource_ $arr_resource_name>].swap(index, other_index);
+                    Ok(())
+                } else {
+                    Err($crate::ECSError::ArrayResourceWriteOutOfBoundsError)
+                }
+            }
+
+            /// Take the value of Array Resource at index, leaving
+            /// the default value in its place
+            fn [<take_resource_ $arr_resource_name>](
+                &mut self,
+                index: usize
+            ) -> Result<$arr_resource_ty, $crate::ECSError> {
+                Ok(std::mem::take(self.[<get_resource_ $arr_resource_name _mut>](index)?))
+            }
+
+            /// Get all values of Array Resource
+            fn [<get_resource_ $arr_resource_name _slice>](&self) -> &[$arr_resource_ty] {
+                &self.[<resource_ $arr_resource_name>]
+            }
+
+            /// Get all mutable values of Array Resource
+            fn [<get_resource_ $arr_resource_name _slice_mut>](&mut self) -> &mut [$arr_resource_ty] {
+                self.[<resource_ $arr_resource_name _changed>] = [self._next_change_tick(); $arr_resource_len];
+                &mut self.[<resource_ $arr_resource_name>]
+            }
+
+            /// Iterate over the values of Array Resource
+            fn [<iter_resource_ $arr_resource_name>](&self) -> std::slice::Iter<'_, $arr_resource_ty> {
+                self.[<resource_ $arr_resource_name>].iter()
+            }
+
+            /// Iterate over the mutable values of Array Resource
+            fn [<iter_resource_ $arr_resource_name _mut>](&mut self) -> std::slice::IterMut<'_, $arr_resource_ty> {
+                self.[<resource_ $arr_resource_name _changed>] = [self._next_change_tick(); $arr_resource_len];
+                self.[<resource_ $arr_resource_name>].iter_mut()
+            }
+
+            /// Checks if any value of Array Resource changed after tick
+            fn [<resource_ $arr_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $arr_resource_name _changed>].iter().any(|changed| *changed > tick)
+            }
+
+            /// Checks if the value of Array Resource at index changed after tick
+            fn [<resource_ $arr_resource_name _slot_changed_since>](
+                &self,
+                index: usize,
+                tick: usize
+            ) -> Result<bool, $crate::ECSError> {
+                match self.[<resource_ $arr_resource_name _changed>].get(index) {
+                    Some(changed) => Ok(*changed > tick),
+                    None => Err($crate::ECSError::ArrayResourceWriteOutOfBoundsError)
+                }
+            }
+            )*
+
+            // Creating Map Resource methods
+            $(
+            /// Marks the value of Map Resource for key as changed
+            fn [<_change_resource_ $map_resource_name>](
+                &mut self,
+                [<$map_resource_name _key>]: &$map_resource_key_type
+            ) {
+                let tick = self._next_change_tick();
+                self.[<resource_ $map_resource_name _changed>] = tick;
+                self.[<resource_ $map_resource_name _key_changed>].insert(
+                    [<$map_resource_name _key>].clone(),
+                    tick
+                );
+            }
+
+            /// Write a value to Map Resource for key
+            fn [<write_resource_ $map_resource_name>](
+                &mut self,
+                [<$map_resource_name _key>]: $map_resource_key_type,
+                [<$map_resource_name _value>]: $map_resource_value_type
+            ) -> Option<$map_resource_value_type> {
+                self.[<_change_resource_ $map_resource_name>](&[<$map_resource_name _key>]);
+                self.[<resource_ $map_resource_name>].insert(
+                    [<$map_resource_name _key>],
+                    [<$map_resource_name _value>]
+                )
+            }
+
+            /// Clear all values of Map Resource
+            fn [<clear_resource_ $map_resource_name>](&mut self) {
+                let tick = self._next_change_tick();
+                self.[<resource_ $map_resource_name _changed>] = tick;
+                self.[<resource_ $map_resource_name _removed>] = tick;
+                self.[<resource_ $map_resource_name _key_changed>].clear();
+                self.[<resource_ $map_resource_name>].clear();
+            }
+
+            /// Get the value of Map Resource for key
+            fn [<get_resource_ $map_resource_name>](
+                &self,
+                [<$map_resource_name _key>]: $map_resource_key_type
+            ) -> Option<&$map_resource_value_type> {
+                self.[<resource_ $map_resource_name>].get(&[<$map_resource_name _key>])
+            }
+
+            /// Get the mutable value of Map Resource for key
+            fn [<get_resource_ $map_resource_name _mut>](
+                &mut self,
+                [<$map_resource_name _key>]: $map_resource_key_type
+            ) -> Option<&mut $map_resource_value_type> {
+                if self.[<resource_ $map_resource_name>].contains_key(&[<$map_resource_name _key>]) {
+                    self.[<_change_resource_ $map_resource_name>](&[<$map_resource_name _key>]);
+                }
+                self.[<resource_ $map_resource_name>].get_mut(&[<$map_resource_name _key>])
+            }
+
+            /// Update the value of Map Resource for key in place.
+            /// 
+            /// Returns `false` if there is no value for key.
+            fn [<update_resource_ $map_resource_name>](
+                &mut self,
+                [<$map_resource_name _key>]: $map_resource_key_type,
+                update: impl FnOnce(&mut $map_resource_value_type)
+            ) -> bool {
+                match self.[<get_resource_ $map_resource_name _mut>]([<$map_resource_name _key>]) {
+                    Some(v) => {
+                        update(v);
+                        true
+                    },
+                    None => false
                 }
+            }
 
-                impl [<$entity_name:camel EntityId>] {
-                    /// Creates a new entity Id
-                    pub fn new(id: usize) -> Self {
-                        [<$entity_name:camel EntityId>] {
-                            id,
-                            generation: 0,
-                            valid: true
-                        }
-                    }
+            /// Remove the value of Map Resource for key, returning it
+            fn [<remove_resource_ $map_resource_name>](
+                &mut self,
+                [<$map_resource_name _key>]: $map_resource_key_type
+            ) -> Option<$map_resource_value_type> {
+                let removed = self.[<resource_ $map_resource_name>].remove(&[<$map_resource_name _key>]);
+                if removed.is_some() {
+                    self.[<_remove_key_resource_ $map_resource_name>](&[<$map_resource_name _key>]);
+                }
+                removed
+            }
 
-                    /// Creates a new entity Id in a invalid state
-                    pub fn new_invalidated(id: usize) -> Self {
-                        [<$entity_name:camel EntityId>] {
-                            id,
-                            generation: 0,
-                            valid: false
-                        }
-                    }
+            /// Marks the value of Map Resource for key as removed,
+            /// dropping the change tick of key
+            fn [<_remove_key_resource_ $map_resource_name>](
+                &mut self,
+                [<$map_resource_name _key>]: &$map_resource_key_type
+            ) {
+                let tick = self._next_change_tick();
+                self.[<resource_ $map_resource_name _changed>] = tick;
+                self.[<resource_ $map_resource_name _removed>] = tick;
+                self.[<resource_ $map_resource_name _key_changed>].remove([<$map_resource_name _key>]);
+            }
 
-                    /// Returns Id valid state
-                    pub fn is_valid(&self) -> bool {
-                        self.valid
-                    }
+            /// Checks if Map Resource has a value for key
+            fn [<contains_resource_ $map_resource_name>](
+                &self,
+                [<$map_resource_name _key>]: $map_resource_key_type
+            ) -> bool {
+                self.[<resource_ $map_resource_name>].contains_key(&[<$map_resource_name _key>])
+            }
 
-                    /// Invalidates entity Id
-                    fn invalidate(&mut self) {
-                        self.valid = false;
-                    }
+            /// Get the number of values of Map Resource
+            fn [<len_resource_ $map_resource_name>](&self) -> usize {
+                self.[<resource_ $map_resource_name>].len()
+            }
 
-                    /// Recovers Id to a valid state advancing generation
-                    fn revalidate(&mut self) {
-                        self.valid = true;
-                        self.generation = self.generation + 1;
-                    }
+            /// Iterate over the keys and values of Map Resource.
+            /// 
+            /// Ordered Map Resources iterate in key order.
+            fn [<iter_resource_ $map_resource_name>](
+                &self
+            ) -> impl Iterator<Item = (&$map_resource_key_type, &$map_resource_value_type)> {
+                self.[<resource_ $map_resource_name>].iter()
+            }
 
-                    pub fn get_id(&self) -> &usize {
-                        &self.id
-                    }
+            /// Iterate over the keys and mutable values of Map Resource.
+            /// 
+            /// Ordered Map Resources iterate in key order.
+            fn [<iter_resource_ $map_resource_name _mut>](
+                &mut self
+            ) -> impl Iterator<Item = (&$map_resource_key_type, &mut $map_resource_value_type)> {
+                let tick = self._next_change_tick();
+                self.[<resource_ $map_resource_name _changed>] = tick;
+                for [<$map_resource_name _key>] in self.[<resource_ $map_resource_name>].keys() {
+                    self.[<resource_ $map_resource_name _key_changed>].insert(
+                        [<$map_resource_name _key>].clone(),
+                        tick
+                    );
                 }
+                self.[<resource_ $map_resource_name>].iter_mut()
+            }
 
-                impl PartialEq for [<$entity_name:camel EntityId>] {
-                    /// Compares if two entities have Id and are from
-                    /// the same generation
-                    fn eq(&self, rhs: &Self) -> bool {
-                        (self.id == rhs.id) && (self.generation == rhs.generation)
+            /// Keep only the values of Map Resource for which `keep` returns `true`
+            fn [<retain_resource_ $map_resource_name>](
+                &mut self,
+                mut keep: impl FnMut(&$map_resource_key_type, &mut $map_resource_value_type) -> bool
+            ) {
+                let tick = self._next_change_tick();
+                self.[<resource_ $map_resource_name _changed>] = tick;
+                let key_changed = &mut self.[<resource_ $map_resource_name _key_changed>];
+                let mut removed = false;
+                self.[<resource_ $map_resource_name>].retain(|[<$map_resource_name _key>], [<$map_resource_name _value>]| {
+                    let kept = keep([<$map_resource_name _key>], [<$map_resource_name _value>]);
+                    if !kept {
+                        key_changed.remove([<$map_resource_name _key>]);
+                        removed = true;
                     }
+                    kept
+                });
+                if removed {
+                    self.[<resource_ $map_resource_name _removed>] = tick;
                 }
+            }
 
-                impl PartialOrd for [<$entity_name:camel EntityId>] {
-                    /// Compares the Ids of two entities
-                    fn partial_cmp(&self, rhs: &Self) -> Option<std::cmp::Ordering> {
-                        Some(self.id.cmp(&rhs.id))
-                    }
+            /// Manipulates the entry of Map Resource for key in place,
+            /// returning the result of `manipulate`.
+            /// 
+            /// The key is only marked as changed if it has a value afterwards,
+            /// or if its value was removed.
+            fn [<entry_resource_ $map_resource_name>]<R>(
+                &mut self,
+                [<$map_resource_name _key>]: $map_resource_key_type,
+                manipulate: impl FnOnce(
+                    $crate::__create_ecs_map_entry_type!(
+                        '_ $($map_resource_kind)? <$map_resource_key_type, $map_resource_value_type>
+                    )
+                ) -> R
+            ) -> R {
+                let existed = self.[<resource_ $map_resource_name>].contains_key(&[<$map_resource_name _key>]);
+                let result = manipulate(
+                    self.[<resource_ $map_resource_name>].entry([<$map_resource_name _key>].clone())
+                );
+                if self.[<resource_ $map_resource_name>].contains_key(&[<$map_resource_name _key>]) {
+                    self.[<_change_resource_ $map_resource_name>](&[<$map_resource_name _key>]);
+                } else if existed {
+                    self.[<_remove_key_resource_ $map_resource_name>](&[<$map_resource_name _key>]);
                 }
+                result
+            }
 
-                impl Eq for [<$entity_name:camel EntityId>] {}
+            /// Checks if any value of Map Resource changed after tick
+            fn [<resource_ $map_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $map_resource_name _changed>] > tick
+            }
 
-                impl Ord for [<$entity_name:camel EntityId>] {
-                    /// Compares the Ids of two entities
-                    fn cmp(&self, rhs: &Self) -> std::cmp::Ordering {
-                        self.id.cmp(&rhs.id)
-                    }
+            /// Checks if the value of Map Resource for key changed after tick.
+            /// 
+            /// Removed keys are not tracked individually, so keys without a value
+            /// are considered changed if any key was removed after tick.
+            fn [<resource_ $map_resource_name _key_changed_since>](
+                &self,
+                [<$map_resource_name _key>]: $map_resource_key_type,
+                tick: usize
+            ) -> bool {
+                match self.[<resource_ $map_resource_name _key_changed>].get(&[<$map_resource_name _key>]) {
+                    Some(changed) => *changed > tick,
+                    None => self.[<resource_ $map_resource_name _removed>] > tick
                 }
+            }
             )*
-        }
 
-        mod entity {
+            // Creating Optional Resource methods
             $(
-                pub struct [<$entity_name:camel Entity>] {
-                    $(pub $comp_name: Option<$comp_type>),*
+            /// Writes a value to Optional Resource
+            fn [<write_resource_ $opt_resource_name>](&mut self, $opt_resource_name: $opt_resource_ty) {
+                self.[<resource_ $opt_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $opt_resource_name>] = Some($opt_resource_name);
+            }
+
+            /// Clears value of Optiomal Resource
+            fn [<clear_resource_ $opt_resource_name>](&mut self) {
+                self.[<resource_ $opt_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $opt_resource_name>] = None;
+            }
+
+            /// Gets value of Optional Resource
+            fn [<get_resource_ $opt_resource_name>](&self) -> Option<&$opt_resource_ty> {
+                match &self.[<resource_ $opt_resource_name>] {
+                    Some(v) => Some(&v),
+                    None => None
                 }
+            }
 
-                pub struct [<$entity_name:camel EntityView>]<'a> {
-                    $(pub $comp_name: Option<&'a $comp_type>),*
+            /// Gets mutable value of Optional Resource
+            fn [<get_resource_ $opt_resource_name _mut>](&mut self) -> Option<&mut $opt_resource_ty> {
+                if self.[<resource_ $opt_resource_name>].is_some() {
+                    self.[<resource_ $opt_resource_name _changed>] = self._next_change_tick();
+                }
+                self.[<resource_ $opt_resource_name>].as_mut()
+            }
+
+            /// Updates value of Optional Resource in place.
+            /// 
+            /// Returns `false` if there is no value.
+            fn [<update_resource_ $opt_resource_name>](&mut self, update: impl FnOnce(&mut $opt_resource_ty)) -> bool {
+                match self.[<get_resource_ $opt_resource_name _mut>]() {
+                    Some(v) => {
+                        update(v);
+                        true
+                    },
+                    None => false
                 }
+            }
+
+            /// Checks if Optional Resource changed after tick
+            fn [<resource_ $opt_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $opt_resource_name _changed>] > tick
+            }
             )*
-        }
 
-        struct $name {
-            // Required resource properties
-            $([<resource_ $resource_name>]: $resource_ty,)*
-            // Array resource properties
-            $([<resource_ $arr_resource_name>]: [$arr_resource_ty; $arr_resource_len],)*
-            // Map resource properties
-            $([<resource_ $map_resource_name>]: std::collections::HashMap<$map_resource_key_type, $map_resource_value_type>,)*
-            // Optional resource properties
-            $([<resource_ $opt_resource_name>]: Option<$opt_resource_ty>,)*
-            // Archtype member properties
+            // Creating Vec Resource methods
             $(
-                // Entity vector member property
-                $entity_name: Vec<id::[<$entity_name:camel EntityId>]>,
-                // Entity components member properties
-                $(
-                    [<$entity_name _ $comp_name>]: Vec<(usize, $comp_type)>,
-                )*
-            )+
-        }
+            /// Pushes a value to the end of Vec Resource
+            fn [<push_resource_ $vec_resource_name>](&mut self, $vec_resource_name: $vec_resource_ty) {
+                self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $vec_resource_name>].push($vec_resource_name);
+            }
 
-        impl $name {
-            /// Create a new instance of ECS struct
-            fn new() -> $name {
-                $name {
-                    // Required resources default initialization
-                    $([<resource_ $resource_name>]: $resource_ty::default(),)*
-                    // Array resources default list initialization
-                    $([<resource_ $arr_resource_name>]: [$arr_resource_ty::default(); $arr_resource_len],)*
-                    // Map resources empty map initialization
-                    $([<resource_ $map_resource_name>]: std::collections::HashMap::new(),)*
-                    // Optional resources None initialization
-                    $([<resource_ $opt_resource_name>]: None,)*
-                    // Archtype storage initialization
-                    $(
-                        // Entity empty list initialization
-                        $entity_name: vec![],
-                        // Entity components  empty list initialization
-                        $(
-                            [<$entity_name _ $comp_name>]: vec![],
-                        )*
-                    )+
+            /// Pops the value at the end of Vec Resource
+            fn [<pop_resource_ $vec_resource_name>](&mut self) -> Option<$vec_resource_ty> {
+                let popped = self.[<resource_ $vec_resource_name>].pop();
+                if popped.is_some() {
+                    self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
                 }
+                popped
             }
 
-            // Creating Resource methods
-            $(
-            /// Write a value to Resource
-            fn [<write_resource_ $resource_name>](&mut self, $resource_name: $resource_ty) {
-                self.[<resource_ $resource_name>] = $resource_name;
+            /// Pushes all values to the end of Vec Resource
+            fn [<extend_resource_ $vec_resource_name>](
+                &mut self,
+                [<$vec_resource_name _values>]: impl IntoIterator<Item = $vec_resource_ty>
+            ) {
+                self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $vec_resource_name>].extend([<$vec_resource_name _values>]);
             }
 
-            /// Get the value of Resource
-            fn [<get_resource_ $resource_name>](&self) -> &$resource_ty {
-                &self.[<resource_ $resource_name>]
+            /// Clears all values of Vec Resource
+            fn [<clear_resource_ $vec_resource_name>](&mut self) {
+                self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $vec_resource_name>].clear();
+            }
+
+            /// Get the value of Vec Resource at index
+            fn [<get_resource_ $vec_resource_name>](&self, index: usize) -> Option<&$vec_resource_ty> {
+                self.[<resource_ $vec_resource_name>].get(index)
+            }
+
+            /// Get the number of values of Vec Resource
+            fn [<len_resource_ $vec_resource_name>](&self) -> usize {
+                self.[<resource_ $vec_resource_name>].len()
+            }
+
+            /// Iterate over the values of Vec Resource
+            fn [<iter_resource_ $vec_resource_name>](&self) -> std::slice::Iter<'_, $vec_resource_ty> {
+                self.[<resource_ $vec_resource_name>].iter()
+            }
+
+            /// Iterate over the mutable values of Vec Resource
+            fn [<iter_resource_ $vec_resource_name _mut>](&mut self) -> std::slice::IterMut<'_, $vec_resource_ty> {
+                self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $vec_resource_name>].iter_mut()
+            }
+
+            /// Checks if Vec Resource changed after tick
+            fn [<resource_ $vec_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $vec_resource_name _changed>] > tick
             }
             )*
 
-            // Creating Array Resource methods
+            // Creating Ring Resource methods
             $(
-            /// Write a value to Array Resource at index
-            fn [<write_resource_ $arr_resource_name>](
+            /// Pushes a value to Ring Resource.
+            /// 
+            /// If Ring Resource is full, the oldest value is removed
+            /// and returned.
+            fn [<push_resource_ $ring_resource_name>](
                 &mut self,
-                $arr_resource_name: $arr_resource_ty,
-                index: usize
-            ) -> Result<(), ECSError> {
-                if index < self.[<resource_ $arr_resource_name>].len() {
-                    self.[<resource_ $arr_resource_name>][index] = $arr_resource_name;
-                    Ok(())
-                } else {
-                    Err(ECSError::ArrayResourceWriteOutOfBoundsError)
+                $ring_resource_name: $ring_resource_ty
+            ) -> Option<$ring_resource_ty> {
+                self.[<resource_ $ring_resource_name _changed>] = self._next_change_tick();
+                let mut oldest = None;
+                while self.[<resource_ $ring_resource_name>].len() >= $ring_resource_len {
+                    oldest = self.[<resource_ $ring_resource_name>].pop_front();
                 }
+                self.[<resource_ $ring_resource_name>].push_back($ring_resource_name);
+                oldest
             }
 
-            /// Clear all values of Array Resource
-            fn [<clear_resource_ $arr_resource_name>](&mut self) {
-                self.[<resource_ $arr_resource_name>] = [$arr_resource_ty::default(); $arr_resource_len];
+            /// Clears all values of Ring Resource
+            fn [<clear_resource_ $ring_resource_name>](&mut self) {
+                self.[<resource_ $ring_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $ring_resource_name>].clear();
             }
 
-            /// Get the value of Array Resource at index
-            fn [<get_resource_ $arr_resource_name>](
-                &self,
-                index: usize
-            ) -> Result<&$arr_resource_ty, ECSError> {
-                if index < self.[<resource_ $arr_resource_name>].len() {
-                    let v = &self.[<resource_ $arr_resource_name>][index];
-                    Ok(&v)
-                } else {
-                    Err(ECSError::ArrayResourceWriteOutOfBoundsError)
-                }
+            /// Get the value of Ring Resource at index, counting
+            /// from the oldest value
+            fn [<get_resource_ $ring_resource_name>](&self, index: usize) -> Option<&$ring_resource_ty> {
+                self.[<resource_ $ring_resource_name>].get(index)
+            }
+
+            /// Get the newest value of Ring Resource
+            fn [<get_resource_ $ring_resource_name _newest>](&self) -> Option<&$ring_resource_ty> {
+                self.[<resource_ $ring_resource_name>].back()
+            }
+
+            /// Get the number of values of Ring Resource
+            fn [<len_resource_ $ring_resource_name>](&self) -> usize {
+                self.[<resource_ $ring_resource_name>].len()
+            }
+
+            /// Iterate over the values of Ring Resource from
+            /// oldest to newest
+            fn [<iter_resource_ $ring_resource_name>](
+                &self
+            ) -> std::collections::vec_deque::Iter<'_, $ring_resource_ty> {
+                self.[<resource_ $ring_resource_name>].iter()
+            }
+
+            /// Iterate over the mutable values of Ring Resource from
+            /// oldest to newest
+            fn [<iter_resource_ $ring_resource_name _mut>](
+                &mut self
+            ) -> std::collections::vec_deque::IterMut<'_, $ring_resource_ty> {
+                self.[<resource_ $ring_resource_name _changed>] = self._next_change_tick();
+                self.[<resource_ $ring_resource_name>].iter_mut()
+            }
+
+            /// Checks if Ring Resource changed after tick
+            fn [<resource_ $ring_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $ring_resource_name _changed>] > tick
             }
             )*
 
-            // Creating Array Resource methods
+            // Creating Locked Resource methods
             $(
-            /// Write a value to Map Resource for key
-            fn [<write_resource_ $map_resource_name>](
-                &mut self,
-                [<$map_resource_name _key>]: $map_resource_key_type,
-                [<$map_resource_name _value>]: $map_resource_value_type
-            ) -> Option<$map_resource_value_type> {
-                self.[<resource_ $map_resource_name>].insert(
-                    [<$map_resource_name _key>],
-                    [<$map_resource_name _value>]
-                )
+            /// Locks Locked Resource for reading, blocking while it is
+            /// locked for writing.
+            /// 
+            /// A lock poisoned by a panicking writer is recovered.
+            fn [<read_resource_ $lock_resource_name>](
+                &self
+            ) -> std::sync::RwLockReadGuard<'_, $lock_resource_ty> {
+                self.[<resource_ $lock_resource_name>]
+                    .read()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
             }
 
-            /// Clear all values of Array Resource
-            fn [<clear_resource_ $map_resource_name>](&mut self) {
-                self.[<resource_ $map_resource_name>].clear();
+            /// Locks Locked Resource for writing, blocking while it is
+            /// locked by readers or another writer.
+            /// 
+            /// A lock poisoned by a panicking writer is recovered.
+            fn [<lock_resource_ $lock_resource_name>](
+                &self
+            ) -> std::sync::RwLockWriteGuard<'_, $lock_resource_ty> {
+                let guard = self.[<resource_ $lock_resource_name>]
+                    .write()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner);
+                self.[<resource_ $lock_resource_name _changed>].store(
+                    self._next_change_tick(),
+                    std::sync::atomic::Ordering::Release
+                );
+                guard
             }
 
-            /// Get the value of Map Resource for key
-            fn [<get_resource_ $map_resource_name>](
-                &self,
-                [<$map_resource_name _key>]: $map_resource_key_type
-            ) -> Option<&$map_resource_value_type> {
-                self.[<resource_ $map_resource_name>].get(&[<$map_resource_name _key>])
+            /// Write a value to Locked Resource
+            fn [<write_resource_ $lock_resource_name>](&self, $lock_resource_name: $lock_resource_ty) {
+                *self.[<lock_resource_ $lock_resource_name>]() = $lock_resource_name;
+            }
+
+            /// Get the mutable value of Locked Resource without locking
+            fn [<get_resource_ $lock_resource_name _mut>](&mut self) -> &mut $lock_resource_ty {
+                *self.[<resource_ $lock_resource_name _changed>].get_mut() = self._next_change_tick();
+                self.[<resource_ $lock_resource_name>]
+                    .get_mut()
+                    .unwrap_or_else(std::sync::PoisonError::into_inner)
+            }
+
+            /// Checks if Locked Resource was changed after tick
+            fn [<resource_ $lock_resource_name _changed_since>](&self, tick: usize) -> bool {
+                self.[<resource_ $lock_resource_name _changed>].load(std::sync::atomic::Ordering::Acquire) > tick
             }
             )*
 
-            // Creating Optional Resource methods
-            $(
-            /// Writes a value to Optional Resource
-            fn [<write_resource_ $opt_resource_name>](&mut self, $opt_resource_name: $opt_resource_ty) {
-                self.[<resource_ $opt_resource_name>] = Some($opt_resource_name);
-            }
+            // Creating Event methods
+            $(
+            /// Sends an Event
+            fn [<send_ $event_name>](&mut self, $event_name: $event_ty) {
+                self.[<event_ $event_name>].send($event_name);
+            }
+
+            /// Creates a reader for Event that starts at the oldest
+            /// Event still in the queue
+            fn [<reader_ $event_name>](&self) -> $crate::ECSEventReader<$event_ty> {
+                self.[<event_ $event_name>].reader()
+            }
+
+            /// Reads the Events not yet read by `reader`
+            fn [<read_ $event_name>]<'a>(
+                &'a self,
+                reader: &mut $crate::ECSEventReader<$event_ty>
+            ) -> impl Iterator<Item = &'a $event_ty> {
+                self.[<event_ $event_name>].read(reader)
+            }
+
+            /// Drops all values of Event
+            fn [<clear_events_ $event_name>](&mut self) {
+                self.[<event_ $event_name>].clear();
+            }
+            )*
+
+            /// Swaps the buffers of all Events.
+            /// 
+            /// Should be called once per run, Events are dropped on
+            /// the second call after they were sent.
+            fn update_events(&mut self) {
+                $(self.[<event_ $event_name>].update();)*
+            }
+
+            /// Creates an empty Commands buffer
+            fn commands(&self) -> command::Commands {
+                command::Commands {
+                    queue: vec![],
+                    $($entity_name: self.[<$entity_name _id_allocator>].clone(),)+
+                }
+            }
+
+            /// Applies Commands in the order they were recorded,
+            /// followed by the commands queued by observers.
+            /// 
+            /// Stops at the first command that fails, dropping the
+            /// commands after it.
+            fn apply_commands(&mut self, mut commands: command::Commands) -> Result<(), $crate::ECSError> {
+                let mut queue = std::mem::take(&mut commands.queue).into_iter();
+                while let Some(command) = queue.next() {
+                    if let Err(error) = self._apply_command(command) {
+                        // Dropped commands release the Ids of their spawns
+                        commands.queue.extend(queue);
+                        return Err(error);
+                    }
+                }
+                self.flush_commands()
+            }
+
+            /// Applies the commands queued by observers, including the
+            /// ones queued while applying them.
+            /// 
+            /// Stops at the first command that fails, dropping the
+            /// commands after it.
+            fn flush_commands(&mut self) -> Result<(), $crate::ECSError> {
+                while !self.pending_commands.is_empty() {
+                    let mut queue = std::mem::take(&mut self.pending_commands).into_iter();
+                    while let Some(command) = queue.next() {
+                        if let Err(error) = self._apply_command(command) {
+                            // Dropped commands release the Ids of their spawns
+                            let mut dropped = self.commands();
+                            dropped.queue.extend(queue);
+                            return Err(error);
+                        }
+                    }
+                }
+                Ok(())
+            }
+
+            /// Applies a single recorded command
+            fn _apply_command(&mut self, command: command::Command) -> Result<(), $crate::ECSError> {
+                match command {
+                    $(
+                        command::Command::[<WriteResource $resource_name:camel>]($resource_name) => {
+                            self.[<write_resource_ $resource_name>]($resource_name);
+                        },
+                    )*
+                    $(
+                        command::Command::[<WriteResource $arr_resource_name:camel>]($arr_resource_name, index) => {
+                            self.[<write_resource_ $arr_resource_name>]($arr_resource_name, index)?;
+                        },
+                        command::Command::[<ClearResource $arr_resource_name:camel>] => {
+                            self.[<clear_resource_ $arr_resource_name>]();
+                        },
+                    )*
+                    $(
+                        command::Command::[<WriteResource $map_resource_name:camel>](
+                            [<$map_resource_name _key>],
+                            [<$map_resource_name _value>]
+                        ) => {
+                            self.[<write_resource_ $map_resource_name>](
+                                [<$map_resource_name _key>],
+                                [<$map_resource_name _value>]
+                            );
+                        },
+                        command::Command::[<ClearResource $map_resource_name:camel>] => {
+                            self.[<clear_resource_ $map_resource_name>]();
+                        },
+                    )*
+                    $(
+                        command::Command::[<WriteResource $opt_resource_name:camel>]($opt_resource_name) => {
+                            self.[<write_resource_ $opt_resource_name>]($opt_resource_name);
+                        },
+                        command::Command::[<ClearResource $opt_resource_name:camel>] => {
+                            self.[<clear_resource_ $opt_resource_name>]();
+                        },
+                    )*
+                    $(
+                        command::Command::[<PushResource $vec_resource_name:camel>]($vec_resource_name) => {
+                            self.[<push_resource_ $vec_resource_name>]($vec_resource_name);
+                        },
+                        command::Command::[<ClearResource $vec_resource_name:camel>] => {
+                            self.[<clear_resource_ $vec_resource_name>]();
+                        },
+                    )*
+                    $(
+                        command::Command::[<PushResource $ring_resource_name:camel>]($ring_resource_name) => {
+                            self.[<push_resource_ $ring_resource_name>]($ring_resource_name);
+                        },
+                        command::Command::[<ClearResource $ring_resource_name:camel>] => {
+                            self.[<clear_resource_ $ring_resource_name>]();
+                        },
+                    )*
+                    $(
+                        command::Command::[<WriteResource $lock_resource_name:camel>]($lock_resource_name) => {
+                            *self.[<get_resource_ $lock_resource_name _mut>]() = $lock_resource_name;
+                        },
+                    )*
+                    $(
+                        command::Command::[<Spawn $entity_name:camel>]([<$entity_name _id>], $entity_name) => {
+                            // The reserved Id may have been taken by `create_`
+                            if self.[<_live_ $entity_name>](*[<$entity_name _id>].get_id()).is_some() {
+                                return Err($crate::ECSError::EntityIdInUseError);
+                            }
+                            self.[<_create_ $entity_name>]([<$entity_name _id>], $entity_name)?;
+                        },
+                        command::Command::[<Delete $entity_name:camel>]([<$entity_name _id>]) => {
+                            self.[<delete_ $entity_name>](&[<$entity_name _id>]);
+                        },
+                        $($(
+                            command::Command::[<Add $req_name:camel To $entity_name:camel>](
+                                [<$entity_name _id>],
+                                $req_name
+                            ) => {
+                                self.[<add_ $req_name _to_ $entity_name>](&[<$entity_name _id>], $req_name);
+                            },
+                        )*)?
+                        $(
+                            command::Command::[<Add $comp_name:camel To $entity_name:camel>](
+                                [<$entity_name _id>],
+                                $comp_name
+                            ) => {
+                                self.[<add_ $comp_name _to_ $entity_name>](&[<$entity_name _id>], $comp_name);
+                            },
+                            command::Command::[<Remove $comp_name:camel From $entity_name:camel>]([<$entity_name _id>]) => {
+                                self.[<remove_ $comp_name _from_ $entity_name>](&[<$entity_name _id>]);
+                            },
+                        )*
+                    )+
+                }
+                Ok(())
+            }
+
+            // Creating Entity Archtype methods
+            $(
+                /// Registers an observer called after an entity is
+                /// spawned or deleted.
+                /// 
+                /// Commands queued by the observer are applied on the next
+                /// `apply_commands` or `flush_commands`.
+                fn [<observe_ $entity_name>](
+                    &mut self,
+                    event: $crate::ECSEntityEvent,
+                    observer: impl FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync + 'static
+                ) {
+                    self.[<$entity_name _observers>].push((event, Box::new(observer)));
+                }
+
+                /// Calls the observers of entity for event
+                fn [<_notify_ $entity_name>](
+                    &mut self,
+                    event: $crate::ECSEntityEvent,
+                    [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                ) {
+                    if self.[<$entity_name _observers>].is_empty() {
+                        return;
+                    }
+                    let mut commands = self.commands();
+                    for (observed, observer) in self.[<$entity_name _observers>].iter_mut() {
+                        if *observed == event {
+                            observer([<$entity_name _id>], &mut commands);
+                        }
+                    }
+                    self.pending_commands.append(&mut commands.queue);
+                }
+
+                $($(
+                    /// Registers an observer called after a required component
+                    /// is added to, changed on or removed from an entity.
+                    /// 
+                    /// Commands queued by the observer are applied on the next
+                    /// `apply_commands` or `flush_commands`.
+                    fn [<observe_ $req_name _of_ $entity_name>](
+                        &mut self,
+                        event: $crate::ECSComponentEvent,
+                        observer: impl FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync + 'static
+                    ) {
+                        self.[<$entity_name _ $req_name _observers>].push((event, Box::new(observer)));
+                    }
+
+                    /// Calls the observers of required component for event
+                    fn [<_notify_ $req_name _of_ $entity_name>](
+                        &mut self,
+                        event: $crate::ECSComponentEvent,
+                        [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                    ) {
+                        if self.[<$entity_name _ $req_name _observers>].is_empty() {
+                            return;
+                        }
+                        let mut commands = self.commands();
+                        for (observed, observer) in self.[<$entity_name _ $req_name _observers>].iter_mut() {
+                            if *observed == event {
+                                observer([<$entity_name _id>], &mut commands);
+                            }
+                        }
+                        self.pending_commands.append(&mut commands.queue);
+                    }
+                )*)?
+
+                $(
+                    /// Registers an observer called after a component is
+                    /// added to, changed on or removed from an entity.
+                    /// 
+                    /// Commands queued by the observer are applied on the next
+                    /// `apply_commands` or `flush_commands`.
+                    fn [<observe_ $comp_name _of_ $entity_name>](
+                        &mut self,
+                        event: $crate::ECSComponentEvent,
+                        observer: impl FnMut(&id::[<$entity_name:camel EntityId>], &mut command::Commands) + Send + Sync + 'static
+                    ) {
+                        self.[<$entity_name _ $comp_name _observers>].push((event, Box::new(observer)));
+                    }
+
+                    /// Calls the observers of component for event
+                    fn [<_notify_ $comp_name _of_ $entity_name>](
+                        &mut self,
+                        event: $crate::ECSComponentEvent,
+                        [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                    ) {
+                        if self.[<$entity_name _ $comp_name _observers>].is_empty() {
+                            return;
+                        }
+                        let mut commands = self.commands();
+                        for (observed, observer) in self.[<$entity_name _ $comp_name _observers>].iter_mut() {
+                            if *observed == event {
+                                observer([<$entity_name _id>], &mut commands);
+                            }
+                        }
+                        self.pending_commands.append(&mut commands.queue);
+                    }
+                )*
+
+                /// Initial capacity of the entity list and every component
+                /// storage of Entity
+                const [<$entity_name:upper _CAPACITY>]: usize = $crate::__create_ecs_initial_value!(
+                    { $crate::__create_ecs_initial_value!({ 0 } $(= $entity_capacity)?) }
+                    $(= $entity_fixed_capacity)?
+                );
 
-            /// Clears value of Optiomal Resource
-            fn [<clear_resource_ $opt_resource_name>](&mut self) {
-                self.[<resource_ $opt_resource_name>] = None;
-            }
+                /// Maximum number of entities of Entity, if it has a fixed capacity
+                const [<$entity_name:upper _FIXED_CAPACITY>]: Option<usize> = $crate::__create_ecs_initial_value!(
+                    { None } $(= Some($entity_fixed_capacity))?
+                );
 
-            /// Gets value of Optional Resource
-            fn [<get_resource_ $opt_resource_name>](&self) -> Option<&$opt_resource_ty> {
-                match &self.[<resource_ $opt_resource_name>] {
-                    Some(v) => Some(&v),
-                    None => None
+                /// Reserves room for at least `additional` more entities
+                /// in the entity list and every component storage of Entity
+                fn [<reserve_ $entity_name>](&mut self, additional: usize) {
+                    $crate::ECSComponentStorage::reserve(&mut self.[<$entity_name>], additional);
+                    self.[<$entity_name _id_allocator>].reserve_capacity(additional);
+                    $crate::__create_ecs_if_required!(
+                        [$(required $($req_name)*)?]
+                        $crate::ECSComponentStorage::reserve(&mut self.[<$entity_name _rows>], additional);
+                    );
+                    $($(
+                        self.[<$entity_name _ $req_name>].reserve(additional);
+                    )*)?
+                    $(
+                        $crate::ECSComponentStorage::reserve(&mut self.[<$entity_name _ $comp_name>], additional);
+                    )*
+                }
+
+                /// Shrinks the entity list and every component storage
+                /// of Entity as much as possible.
+                /// 
+                /// Does nothing if Entity has a fixed capacity, so spawning
+                /// it never allocates.
+                fn [<shrink_ $entity_name>](&mut self) {
+                    if Self::[<$entity_name:upper _FIXED_CAPACITY>].is_some() {
+                        return;
+                    }
+                    $crate::ECSComponentStorage::shrink_to_fit(&mut self.[<$entity_name>]);
+                    self.[<$entity_name _id_allocator>].shrink_to_fit();
+                    $crate::__create_ecs_if_required!(
+                        [$(required $($req_name)*)?]
+                        $crate::ECSComponentStorage::shrink_to_fit(&mut self.[<$entity_name _rows>]);
+                    );
+                    $($(
+                        self.[<$entity_name _ $req_name>].shrink_to_fit();
+                    )*)?
+                    $(
+                        $crate::ECSComponentStorage::shrink_to_fit(&mut self.[<$entity_name _ $comp_name>]);
+                    )*
                 }
-            }
-            )*
 
-            // Creating Entity Archtype methods
-            $(
                 /// Checks if ID has valid entity
                 fn [<has_ $entity_name>](&self, [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]) -> bool {
-                    match self.[<$entity_name>].binary_search([<$entity_name _id>]) {
-                        Ok(ind) => {
-                            let ent = &self.[<$entity_name>][ind];
-                            ent.is_valid()
-                        },
-                        Err(_) => false
-                    }
+                    self.[<_live_ $entity_name>](*[<$entity_name _id>].get_id())
+                        .is_some_and(|ent| ent == [<$entity_name _id>])
                 }
 
+                /// Gets the Id of the valid entity with id, whatever its generation
+                fn [<_live_ $entity_name>](&self, id: usize) -> Option<&id::[<$entity_name:camel EntityId>]> {
+                    $crate::ECSComponentStorage::get(&self.[<$entity_name>], id).filter(|ent| ent.is_valid())
+                }
+
+                $($(
+                    /// Replaces a required component of a Entity.
+                    /// 
+                    /// Does nothing if Id is not of an existing entity, as required
+                    /// components are only added when the entity is created.
+                    fn [<add_ $req_name _to_ $entity_name>](
+                        &mut self,
+                        [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>],
+                        $req_name: $req_type
+                    ) {
+                        if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                            return;
+                        }
+                        if let Some(row) = self.[<$entity_name _rows>].index(*[<$entity_name _id>].get_id()) {
+                            self.[<$entity_name _ $req_name>][row] = $req_name;
+                            $crate::__create_ecs_component_hook!(
+                                on_replace,
+                                [<$entity_name _id>],
+                                &self.[<$entity_name _ $req_name>][row];
+                                $($($req_hook: $req_hook_fn),*)?
+                            );
+                            self.[<_notify_ $req_name _of_ $entity_name>](
+                                $crate::ECSComponentEvent::Changed,
+                                [<$entity_name _id>]
+                            );
+                        }
+                    }
+
+                    /// Gets the required Component from the entity of Id
+                    fn [<get_ $req_name _of_ $entity_name>]<'a>(
+                        &'a self,
+                        [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                    ) -> Option<&'a $req_type> {
+                        if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                            return None;
+                        }
+                        self.[<$entity_name _rows>]
+                            .index(*[<$entity_name _id>].get_id())
+                            .map(|row| &self.[<$entity_name _ $req_name>][row])
+                    }
+                )*)?
+
                 $(
-                    /// Adds a component to a Entity
+                    /// Adds a component to a Entity.
+                    /// 
+                    /// Does nothing if Id is not of an existing entity.
                     fn [<add_ $comp_name _to_ $entity_name>](
                         &mut self,
                         [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>],
                         $comp_name: $comp_type
                     ) {
-                        let pos = self.[<$entity_name _ $comp_name>].binary_search_by_key(
-                            [<$entity_name _id>].get_id(),
-                            |kv| kv.0
+                        if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                            return;
+                        }
+                        let replaced = $crate::ECSComponentStorage::insert(
+                            &mut self.[<$entity_name _ $comp_name>],
+                            *[<$entity_name _id>].get_id(),
+                            $comp_name
                         );
-                        match pos {
-                            Ok(ind) => self.[<$entity_name _ $comp_name>][ind].1 = $comp_name,
-                            Err(ind) => self.[<$entity_name _ $comp_name>].insert(
-                                ind,
-                                (*[<$entity_name _id>].get_id(), $comp_name)
-                            )
+                        match replaced {
+                            Some(_) => {
+                                $crate::__create_ecs_component_hook!(
+                                    on_replace,
+                                    [<$entity_name _id>],
+                                    self.[<get_ $comp_name _of_ $entity_name>]([<$entity_name _id>])
+                                        .expect("Component was just inserted.");
+                                    $($($comp_hook: $comp_hook_fn),*)?
+                                );
+                                self.[<_notify_ $comp_name _of_ $entity_name>](
+                                    $crate::ECSComponentEvent::Changed,
+                                    [<$entity_name _id>]
+                                );
+                            },
+                            None => {
+                                $crate::__create_ecs_component_hook!(
+                                    on_add,
+                                    [<$entity_name _id>],
+                                    self.[<get_ $comp_name _of_ $entity_name>]([<$entity_name _id>])
+                                        .expect("Component was just inserted.");
+                                    $($($comp_hook: $comp_hook_fn),*)?
+                                );
+                                self.[<_notify_ $comp_name _of_ $entity_name>](
+                                    $crate::ECSComponentEvent::Added,
+                                    [<$entity_name _id>]
+                                );
+                            }
                         };
                     }
+
+                    /// Removes a component from a Entity, returning it
+                    fn [<remove_ $comp_name _from_ $entity_name>](
+                        &mut self,
+                        [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                    ) -> Option<$comp_type> {
+                        if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                            return None;
+                        }
+                        let removed = $crate::ECSComponentStorage::remove(
+                            &mut self.[<$entity_name _ $comp_name>],
+                            *[<$entity_name _id>].get_id()
+                        );
+                        match removed {
+                            Some($comp_name) => {
+                                $crate::__create_ecs_component_hook!(
+                                    on_remove,
+                                    [<$entity_name _id>],
+                                    &$comp_name;
+                                    $($($comp_hook: $comp_hook_fn),*)?
+                                );
+                                self.[<_notify_ $comp_name _of_ $entity_name>](
+                                    $crate::ECSComponentEvent::Removed,
+                                    [<$entity_name _id>]
+                                );
+                                Some($comp_name)
+                            },
+                            None => None
+                        }
+                    }
                 )*
 
-                /// Creates a new entity with given Id and Components
+                /// Creates a new entity with given Id and Components.
+                /// 
+                /// Fails with `ECSError::EntityIdOutOfRangeError` if Id is above
+                /// `ECS_MAX_ENTITY_ID`, and with `ECSError::ArchetypeFull` if
+                /// Entity has a fixed capacity and Id is not below it.
                 fn [<_create_ $entity_name>](
                     &mut self,
                     [<$entity_name _id>]: id::[<$entity_name:camel EntityId>],
                     $entity_name: entity::[<$entity_name:camel Entity>]
-                ) -> id::[<$entity_name:camel EntityId>] {
-                    let id = match self.[<$entity_name>].binary_search(&[<$entity_name _id>]) {
-                        Ok(ind) => self.[<$entity_name>][ind].clone(),
-                        Err(ind) => {
-                            self.[<$entity_name>].insert(ind, [<$entity_name _id>]);
-                            self.[<$entity_name>][ind].clone()
+                ) -> Result<id::[<$entity_name:camel EntityId>], $crate::ECSError> {
+                    let raw_id = *[<$entity_name _id>].get_id();
+                    let (id, spawned) = match $crate::ECSComponentStorage::get_mut(&mut self.[<$entity_name>], raw_id) {
+                        Some(ent) => {
+                            let spawned = !ent.is_valid();
+                            if spawned {
+                                ent.revalidate();
+                            }
+                            (ent.clone(), spawned)
+                        },
+                        None => {
+                            if *[<$entity_name _id>].get_id() > $crate::ECS_MAX_ENTITY_ID {
+                                return Err($crate::ECSError::EntityIdOutOfRangeError);
+                            }
+                            // Ids of fixed capacity archetypes are kept below
+                            // the capacity, so no storage grows past it
+                            if let Some(capacity) = Self::[<$entity_name:upper _FIXED_CAPACITY>] {
+                                if *[<$entity_name _id>].get_id() >= capacity {
+                                    return Err($crate::ECSError::ArchetypeFull);
+                                }
+                            }
+                            $crate::ECSComponentStorage::insert(
+                                &mut self.[<$entity_name>],
+                                raw_id,
+                                [<$entity_name _id>].clone()
+                            );
+                            ([<$entity_name _id>], true)
                         }
                     };
 
-                    let entity::[< $entity_name:camel Entity>] {
-                        $($comp_name: $comp_name),*
+                    let entity::[<$entity_name:camel Entity>] {
+                        $($($req_name,)*)?
+                        $($comp_name),*
                     } = $entity_name;
 
+                    $crate::__create_ecs_if_required!(
+                        [$(required $($req_name)*)?]
+                        if $crate::ECSComponentStorage::contains(&self.[<$entity_name _rows>], *id.get_id()) {
+                            $($(
+                                self.[<add_ $req_name _to_ $entity_name>](&id, $req_name);
+                            )*)?
+                        } else {
+                            // Required components are pushed to the end of their
+                            // columns before any hook runs, so rows stay aligned
+                            $crate::ECSComponentStorage::insert(&mut self.[<$entity_name _rows>], *id.get_id(), ());
+                            $($(
+                                self.[<$entity_name _ $req_name>].push($req_name);
+                            )*)?
+                            $($(
+                                $crate::__create_ecs_component_hook!(
+                                    on_add,
+                                    &id,
+                                    self.[<get_ $req_name _of_ $entity_name>](&id)
+                                        .expect("Required component was just inserted.");
+                                    $($($req_hook: $req_hook_fn),*)?
+                                );
+                                self.[<_notify_ $req_name _of_ $entity_name>]($crate::ECSComponentEvent::Added, &id);
+                            )*)?
+                        }
+                    );
+
                     $(
                         if let Some(comp) = $comp_name {
                             self.[<add_ $comp_name _to_ $entity_name>](&id, comp);
                         }
                     )*
-                    id
+                    if spawned {
+                        self.[<_notify_ $entity_name>]($crate::ECSEntityEvent::Spawned, &id);
+                    }
+                    Ok(id)
+                }
+
+                /// Creates a new entity with a new Id and Components.
+                /// 
+                /// The Id of a deleted entity is reused first, advancing its
+                /// generation. Entities with a fixed capacity fail with
+                /// `ECSError::ArchetypeFull` when there is none left.
+                fn [<spawn_ $entity_name>](
+                    &mut self,
+                    $entity_name: entity::[<$entity_name:camel Entity>]
+                ) -> Result<id::[<$entity_name:camel EntityId>], $crate::ECSError> {
+                    let id = loop {
+                        let (id, generation) = self.[<$entity_name _id_allocator>].reserve();
+                        let id = id::[<$entity_name:camel EntityId>]::with_generation(id, generation);
+                        // Ids released by dropped Commands may have been
+                        // taken by `create_` since they were reserved
+                        if self.[<_live_ $entity_name>](*id.get_id()).is_none() {
+                            break id;
+                        }
+                    };
+                    self.[<_create_ $entity_name>](id, $entity_name)
                 }
 
                 /// Creates a new entity with given Id and Components
@@ -377,20 +3476,71 @@ macro_rules! create_ecs {
                     &mut self,
                     [<$entity_name _id>]: id::[<$entity_name:camel EntityId>],
                     $entity_name: entity::[<$entity_name:camel Entity>],
-                    conflict_resolution: ECSEntityCreateConflictResolution
-                ) -> Result<id::[<$entity_name:camel EntityId>], ECSError> {
-                    let exists = self.[<has_ $entity_name>](&[<$entity_name _id>]);
-                    match (conflict_resolution, exists) {
-                        (ECSEntityCreateConflictResolution::Error, true) => Err(ECSError::EntityIdInUseError),
-                        (ECSEntityCreateConflictResolution::Ignore, true) => {
-                            let id = match self.[<$entity_name>].binary_search(&[<$entity_name _id>]) {
-                                Ok(ind) => self.[<$entity_name>][ind].clone(),
-                                Err(_) => panic!("Test for existence of entity returned true, and then false.")
-                            };
+                    conflict_resolution: $crate::ECSEntityCreateConflictResolution
+                ) -> Result<id::[<$entity_name:camel EntityId>], $crate::ECSError> {
+                    // The slot of id conflicts with any valid entity, even one
+                    // of another generation than Id
+                    let existing = self.[<_live_ $entity_name>](*[<$entity_name _id>].get_id()).cloned();
+                    match (conflict_resolution, existing) {
+                        ($crate::ECSEntityCreateConflictResolution::Error, Some(_)) => Err($crate::ECSError::EntityIdInUseError),
+                        ($crate::ECSEntityCreateConflictResolution::Ignore, Some(id)) => Ok(id),
+                        (_) => {
+                            let id = self.[<_create_ $entity_name>]([<$entity_name _id>], $entity_name)?;
+                            // Ids given by the caller are not reserved afterwards
+                            self.[<$entity_name _id_allocator>].mark_used(*id.get_id());
                             Ok(id)
-                        },
-                        (_) => Ok(self.[<_create_ $entity_name>]([<$entity_name _id>], $entity_name))
+                        }
+                    }
+                }
+
+                /// Deletes entity of Id, returning its Components
+                fn [<delete_ $entity_name>](
+                    &mut self,
+                    [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                ) -> Option<entity::[<$entity_name:camel Entity>]> {
+                    if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                        return None;
+                    }
+                    // Components are removed while Id is still valid
+                    // Required components are swap removed like the row,
+                    // so rows stay aligned
+                    $crate::__create_ecs_if_required!(
+                        [$(required $($req_name)*)?]
+                        let row = self.[<$entity_name _rows>]
+                            .index(*[<$entity_name _id>].get_id())
+                            .expect("Existing entity has a row.");
+                        $crate::ECSComponentStorage::remove(&mut self.[<$entity_name _rows>], *[<$entity_name _id>].get_id());
+                    );
+                    $($(
+                        let $req_name = self.[<$entity_name _ $req_name>].swap_remove(row);
+                        $crate::__create_ecs_component_hook!(
+                            on_remove,
+                            [<$entity_name _id>],
+                            &$req_name;
+                            $($($req_hook: $req_hook_fn),*)?
+                        );
+                        self.[<_notify_ $req_name _of_ $entity_name>](
+                            $crate::ECSComponentEvent::Removed,
+                            [<$entity_name _id>]
+                        );
+                    )*)?
+                    let $entity_name = entity::[<$entity_name:camel Entity>] {
+                        $($($req_name,)*)?
+                        $($comp_name: self.[<remove_ $comp_name _from_ $entity_name>]([<$entity_name _id>])),*
+                    };
+                    if let Some(ent) = $crate::ECSComponentStorage::get_mut(
+                        &mut self.[<$entity_name>],
+                        *[<$entity_name _id>].get_id()
+                    ) {
+                        ent.invalidate();
                     }
+                    // The Id is reused with the generation it is revalidated to
+                    self.[<$entity_name _id_allocator>].release(
+                        *[<$entity_name _id>].get_id(),
+                        [<$entity_name _id>].get_generation() + 1
+                    );
+                    self.[<_notify_ $entity_name>]($crate::ECSEntityEvent::Deleted, [<$entity_name _id>]);
+                    Some($entity_name)
                 }
 
                 // Entity components methods
@@ -398,16 +3548,17 @@ macro_rules! create_ecs {
                     &'a self,
                     [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
                 ) -> Option<entity::[<$entity_name:camel EntityView>]> {
-                    match self.[<$entity_name>].binary_search(&[<$entity_name _id>]) {
-                        Ok(ind) => {
-                            Some(
-                                entity::[<$entity_name:camel EntityView>] {
-                                    $($comp_name: self.[<get_ $comp_name _of_ $entity_name>](&[<$entity_name _id>])),*
-                                }
-                            )
-                        },
-                        Err(_) => None
+                    if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                        return None;
                     }
+                    Some(
+                        entity::[<$entity_name:camel EntityView>] {
+                            $($(
+                                $req_name: self.[<get_ $req_name _of_ $entity_name>](&[<$entity_name _id>])?,
+                            )*)?
+                            $($comp_name: self.[<get_ $comp_name _of_ $entity_name>](&[<$entity_name _id>])),*
+                        }
+                    )
                 }
 
                 $(
@@ -416,21 +3567,691 @@ macro_rules! create_ecs {
                         &'a self,
                         [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
                     ) -> Option<&'a $comp_type> {
-                        match self.[<$entity_name _ $comp_name>].binary_search_by_key(
-                            [<$entity_name _id>].get_id(), |kc| { kc.0 }
-                        ) {
-                            Ok(real_index) => {
-                                match self.[<$entity_name _ $comp_name>].get(real_index) {
-                                    Some(kc) => Some(&kc.1),
-                                    None => None
-                                }
-                            },
-                            Err(_) => None
+                        if !self.[<has_ $entity_name>]([<$entity_name _id>]) {
+                            return None;
                         }
+                        $crate::ECSComponentStorage::get(
+                            &self.[<$entity_name _ $comp_name>],
+                            *[<$entity_name _id>].get_id()
+                        )
                     }
                 )*
             )+ // for each archtype end
+
+            // Creating Locked Archtype methods
+            $(
+                /// Initial capacity of the entity list and every component
+                /// storage of Locked Entity
+                const [<$locked_entity_name:upper _CAPACITY>]: usize = $crate::__create_ecs_initial_value!(
+                    { 0 } $(= $locked_entity_capacity)?
+                );
+
+                /// Checks if ID has valid entity
+                fn [<has_ $locked_entity_name>](
+                    &self,
+                    [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                ) -> bool {
+                    self.$locked_entity_name.contains(
+                        *[<$locked_entity_name _id>].get_id(),
+                        *[<$locked_entity_name _id>].get_generation()
+                    )
+                }
+
+                /// Creates a new entity with a new Id and Components.
+                /// 
+                /// The Id of a deleted entity is reused first, advancing its
+                /// generation. The entity is only valid once all of its
+                /// Components are inserted, so other threads never see it
+                /// partially spawned.
+                fn [<spawn_ $locked_entity_name>](
+                    &self,
+                    $locked_entity_name: entity::[<$locked_entity_name:camel Entity>]
+                ) -> Result<id::[<$locked_entity_name:camel EntityId>], $crate::ECSError> {
+                    let (raw_id, generation) = self.[<$locked_entity_name _id_allocator>].reserve();
+                    if raw_id > $crate::ECS_MAX_ENTITY_ID {
+                        return Err($crate::ECSError::EntityIdOutOfRangeError);
+                    }
+                    let entity::[<$locked_entity_name:camel Entity>] {
+                        $($locked_comp_name),*
+                    } = $locked_entity_name;
+                    $(
+                        if let Some(comp) = $locked_comp_name {
+                            self.[<$locked_entity_name _ $locked_comp_name>].insert(raw_id, generation, comp);
+                        }
+                    )*
+                    self.$locked_entity_name.insert(raw_id, generation, ());
+                    Ok(id::[<$locked_entity_name:camel EntityId>]::with_generation(raw_id, generation))
+                }
+
+                /// Deletes entity of Id, returning its Components
+                fn [<delete_ $locked_entity_name>](
+                    &self,
+                    [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                ) -> Option<entity::[<$locked_entity_name:camel Entity>]> {
+                    let raw_id = *[<$locked_entity_name _id>].get_id();
+                    let generation = *[<$locked_entity_name _id>].get_generation();
+                    // Invalidating Id first lets only one of concurrent
+                    // deletes of the entity go on
+                    self.$locked_entity_name.remove(raw_id, generation)?;
+                    let $locked_entity_name = entity::[<$locked_entity_name:camel Entity>] {
+                        $(
+                            $locked_comp_name: self.[<$locked_entity_name _ $locked_comp_name>]
+                                .remove(raw_id, generation)
+                        ),*
+                    };
+                    // The id is only reused once its Components are removed
+                    self.[<$locked_entity_name _id_allocator>].release(raw_id, generation + 1);
+                    Some($locked_entity_name)
+                }
+
+                $(
+                    /// Adds a component to a Locked Entity.
+                    /// 
+                    /// Does nothing if Id is not of an existing entity.
+                    fn [<add_ $locked_comp_name _to_ $locked_entity_name>](
+                        &self,
+                        [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>],
+                        $locked_comp_name: $locked_comp_type
+                    ) {
+                        if !self.[<has_ $locked_entity_name>]([<$locked_entity_name _id>]) {
+                            return;
+                        }
+                        let raw_id = *[<$locked_entity_name _id>].get_id();
+                        let generation = *[<$locked_entity_name _id>].get_generation();
+                        self.[<$locked_entity_name _ $locked_comp_name>].insert(raw_id, generation, $locked_comp_name);
+                        // The entity may have been deleted by another thread
+                        // before the component was inserted
+                        if !self.[<has_ $locked_entity_name>]([<$locked_entity_name _id>]) {
+                            self.[<$locked_entity_name _ $locked_comp_name>].remove(raw_id, generation);
+                        }
+                    }
+
+                    /// Removes a component from a Locked Entity, returning it
+                    fn [<remove_ $locked_comp_name _from_ $locked_entity_name>](
+                        &self,
+                        [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                    ) -> Option<$locked_comp_type> {
+                        self.[<$locked_entity_name _ $locked_comp_name>].remove(
+                            *[<$locked_entity_name _id>].get_id(),
+                            *[<$locked_entity_name _id>].get_generation()
+                        )
+                    }
+
+                    /// Locks the Component of Locked Entity for reading and gets
+                    /// it from the entity of Id, blocking while it is locked
+                    /// for writing.
+                    fn [<read_ $locked_comp_name _of_ $locked_entity_name>](
+                        &self,
+                        [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                    ) -> Option<$crate::ECSLockedReadGuard<'_, $locked_comp_type>> {
+                        self.[<$locked_entity_name _ $locked_comp_name>].read(
+                            *[<$locked_entity_name _id>].get_id(),
+                            *[<$locked_entity_name _id>].get_generation()
+                        )
+                    }
+
+                    /// Locks the Component of Locked Entity for writing and gets
+                    /// it from the entity of Id, blocking while it is locked by
+                    /// readers or another writer.
+                    fn [<write_ $locked_comp_name _of_ $locked_entity_name>](
+                        &self,
+                        [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                    ) -> Option<$crate::ECSLockedWriteGuard<'_, $locked_comp_type>> {
+                        self.[<$locked_entity_name _ $locked_comp_name>].write(
+                            *[<$locked_entity_name _id>].get_id(),
+                            *[<$locked_entity_name _id>].get_generation()
+                        )
+                    }
+
+                    /// Gets the mutable Component from the entity of Id without locking
+                    fn [<get_ $locked_comp_name _of_ $locked_entity_name _mut>](
+                        &mut self,
+                        [<$locked_entity_name _id>]: &id::[<$locked_entity_name:camel EntityId>]
+                    ) -> Option<&mut $locked_comp_type> {
+                        self.[<$locked_entity_name _ $locked_comp_name>].get_mut(
+                            *[<$locked_entity_name _id>].get_id(),
+                            *[<$locked_entity_name _id>].get_generation()
+                        )
+                    }
+                )*
+            )* // for each locked archtype end
+
+            // Creating Systems methods
+            /// Runs the systems of every Stage in order, running the
+            /// systems of every Startup stage first on the first call.
+            /// 
+            /// The systems of a stage run in parallel on scoped threads,
+            /// each recording to its own Commands. Once all of them
+            /// return, the Commands of the systems that did not fail are
+            /// applied in the order the systems are declared. The systems
+            /// of an Exclusive stage take `&mut` of the ECS instead and run
+            /// one after another, between the stages around it. A system
+            /// fails if it returns an error or if applying its Commands
+            /// does, and the error policy of the schedule decides if
+            /// the next stages run.
+            fn run_systems(&mut self) -> $crate::ECSSystemReport {
+                let mut report = $crate::ECSSystemReport::default();
+                self.system_tick += 1;
+                if !self.systems_started {
+                    self.systems_started = true;
+                    self._run_schedule($crate::ECSSchedule::Startup, &mut report);
+                }
+                self._run_schedule($crate::ECSSchedule::Update, &mut report);
+                report
+            }
+
+            /// Runs the systems of every Shutdown stage in order, the
+            /// same way `run_systems` runs the systems of a Stage
+            fn shutdown(&mut self) -> $crate::ECSSystemReport {
+                let mut report = $crate::ECSSystemReport::default();
+                self._run_schedule($crate::ECSSchedule::Shutdown, &mut report);
+                report
+            }
+
+            /// Gets the number of `run_systems` calls, which failures
+            /// are reported with
+            fn system_tick(&self) -> usize {
+                self.system_tick
+            }
+
+            /// Sets what schedule does when one of its systems fails,
+            /// `ECSErrorPolicy::Stop` by default
+            fn set_system_error_policy(&mut self, schedule: $crate::ECSSchedule, policy: $crate::ECSErrorPolicy) {
+                self.system_error_policies[schedule as usize] = policy;
+            }
+
+            /// Runs the enabled systems of every stage of schedule in order
+            fn _run_schedule(&mut self, schedule: $crate::ECSSchedule, report: &mut $crate::ECSSystemReport) {
+                let policy = self.system_error_policies[schedule as usize];
+                $(
+                    if $crate::__create_ecs_stage_schedule!($stage_kind) == schedule {
+                        let failures = report.failures.len();
+                        // Local states are taken out of the ECS while
+                        // their systems run
+                        $(
+                            let mut [<system_ $system_name _local>] = std::mem::take(
+                                &mut self.[<system_ $system_name _local>]
+                            );
+                        )*
+                        $crate::__create_ecs_stage_kind!($stage_kind {
+                            // Statistics are taken out of the ECS while it is
+                            // shared by the systems
+                            $(
+                                let mut [<system_ $system_name _stats>] = std::mem::take(
+                                    &mut self.[<system_ $system_name _stats>]
+                                );
+                            )*
+                            let ecs = &*self;
+                            let results = std::thread::scope(|scope| {
+                                let mut systems: Vec<std::thread::ScopedJoinHandle<'_, (
+                                    &'static str,
+                                    Result<(), $crate::ECSSystemError>,
+                                    command::Commands
+                                )>> = vec![];
+                                $(
+                                    if ecs.[<system_ $system_name _enabled>] {
+                                        let stats = &mut [<system_ $system_name _stats>];
+                                        let local = &mut [<system_ $system_name _local>];
+                                        systems.push(scope.spawn(move || {
+                                            let mut commands = ecs.commands();
+                                            let timer = $crate::ECSSystemTimer::start();
+                                            let result = $crate::ECSSystemOutput::into_result(
+                                                $crate::__create_ecs_call_system!(
+                                                    $system_fn[ecs, &mut commands], local $(, $system_local)?
+                                                )
+                                            );
+                                            stats.record(timer, stringify!($system_name), ecs.system_tick, ecs.system_budget);
+                                            (stringify!($system_name), result, commands)
+                                        }));
+                                    }
+                                )*
+                                systems
+                                    .into_iter()
+                                    .map(|system| system.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+                                    .collect::<Vec<_>>()
+                            });
+                            $(
+                                self.[<system_ $system_name _stats>] = [<system_ $system_name _stats>];
+                            )*
+                            for (system, result, commands) in results {
+                                // The Commands of a failed system are dropped
+                                let result = result.and_then(|()| self.apply_commands(commands).map_err(Into::into));
+                                if let Err(error) = result {
+                                    self._system_failed(policy, report, system, error);
+                                }
+                            }
+                        } {
+                            // Exclusive systems run one after another on
+                            // the calling thread
+                            $(
+                                if self.[<system_ $system_name _enabled>] {
+                                    let timer = $crate::ECSSystemTimer::start();
+                                    let result = $crate::ECSSystemOutput::into_result(
+                                        $crate::__create_ecs_call_system!(
+                                            $system_fn[self], &mut [<system_ $system_name _local>] $(, $system_local)?
+                                        )
+                                    );
+                                    self.[<system_ $system_name _stats>].record(
+                                        timer,
+                                        stringify!($system_name),
+                                        self.system_tick,
+                                        self.system_budget
+                                    );
+                                    // Commands queued by observers while the
+                                    // system ran are applied after it
+                                    let result = result.and_then(|()| self.flush_commands().map_err(Into::into));
+                                    if let Err(error) = result {
+                                        self._system_failed(policy, report, stringify!($system_name), error);
+                                    }
+                                }
+                            )*
+                        });
+                        $(
+                            self.[<system_ $system_name _local>] = [<system_ $system_name _local>];
+                        )*
+                        if policy == $crate::ECSErrorPolicy::Stop && report.failures.len() > failures {
+                            return;
+                        }
+                    }
+                )*
+                if schedule == $crate::ECSSchedule::Update {
+                    self._run_runtime_systems(policy, report);
+                }
+            }
+
+            /// Runs the enabled runtime systems one after another in the
+            /// order they were added.
+            /// 
+            /// The systems are put back even if one of them panics.
+            fn _run_runtime_systems(&mut self, policy: $crate::ECSErrorPolicy, report: &mut $crate::ECSSystemReport) {
+                let mut systems = std::mem::take(&mut self.runtime_systems);
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    for system in systems.iter_mut().filter(|system| system.enabled) {
+                        let timer = $crate::ECSSystemTimer::start();
+                        let result = system.run(self);
+                        system.stats.record(timer, system.name, self.system_tick, self.system_budget);
+                        if let Err(error) = result {
+                            self._system_failed(policy, report, system.name, error);
+                        }
+                    }
+                }));
+                self.runtime_systems = systems;
+                if let Err(panic) = result {
+                    std::panic::resume_unwind(panic);
+                }
+            }
+
+            /// Adds system to run at the end of every `run_systems`,
+            /// after the declared stages.
+            /// 
+            /// The parameters of system are extracted from the ECS on
+            /// every run, as `system::Res` of a Resource, `system::Query`
+            /// of an Archtype, `system::Reader` of an Event or
+            /// `&mut command::Commands`. Its Commands are applied after it
+            /// ran, and dropped if it failed.
+            /// 
+            /// Fails with `ECSError::SystemNameInUseError` if a system
+            /// with name is already declared or added.
+            fn add_system<P, F>(&mut self, name: &'static str, system: F) -> Result<(), $crate::ECSError>
+            where
+                F: $crate::ECSSystemFn<$name, P> + Send + Sync,
+                F::State: Send + Sync
+            {
+                if self.systems().iter().any(|info| info.name == name) {
+                    return Err($crate::ECSError::SystemNameInUseError);
+                }
+                let system = $crate::ECSRuntimeSystem::new(name, self, system);
+                self.runtime_systems.push(system);
+                Ok(())
+            }
+
+            /// Records the failure of system to report, following policy
+            fn _system_failed(
+                &self,
+                policy: $crate::ECSErrorPolicy,
+                report: &mut $crate::ECSSystemReport,
+                system: &'static str,
+                error: $crate::ECSSystemError
+            ) {
+                if policy == $crate::ECSErrorPolicy::Log {
+                    eprintln!("System `{}` failed on tick {}: {}", system, self.system_tick, error);
+                }
+                report.failures.push($crate::ECSSystemFailure {
+                    system,
+                    error,
+                    tick: self.system_tick
+                });
+            }
+
+            $crate::__create_ecs_if_system_stats! {
+                /// Gets the call statistics of the system of name.
+                /// 
+                /// Fails with `ECSError::SystemNotFoundError` if no system is
+                /// declared with name.
+                fn system_stats(&self, name: &str) -> Result<$crate::ECSSystemStats, $crate::ECSError> {
+                    $($(
+                        if name == stringify!($system_name) {
+                            return Ok(self.[<system_ $system_name _stats>]);
+                        }
+                    )*)*
+                    self.runtime_systems
+                        .iter()
+                        .find(|system| system.name == name)
+                        .map(|system| system.stats)
+                        .ok_or($crate::ECSError::SystemNotFoundError)
+                }
+
+                /// Formats the call statistics of every system as a table
+                fn system_stats_table(&self) -> String {
+                    let mut systems = vec![
+                        $($(
+                            (stringify!($system_name), self.[<system_ $system_name _stats>]),
+                        )*)*
+                    ];
+                    systems.extend(self.runtime_systems.iter().map(|system| (system.name, system.stats)));
+                    $crate::ECSSystemStats::table(&systems)
+                }
+
+                /// Sets the duration systems print a warning to stderr
+                /// about when a run takes longer, or `None` to not warn
+                fn set_system_budget(&mut self, budget: Option<std::time::Duration>) {
+                    self.system_budget = budget;
+                }
+            }
+
+            /// Lists the systems in the order they are declared, with
+            /// their stage, declared access and enabled state, followed by
+            /// the runtime systems in a stage of their own
+            #[allow(unused_assignments)]
+            fn systems(&self) -> Vec<$crate::ECSSystemInfo> {
+                let mut systems = vec![];
+                let mut stage = 0;
+                $(
+                    $(
+                        systems.push($crate::ECSSystemInfo {
+                            name: stringify!($system_name),
+                            schedule: $crate::__create_ecs_stage_schedule!($stage_kind),
+                            stage,
+                            exclusive: $crate::__create_ecs_stage_kind!($stage_kind { false } { true }),
+                            reads: &[$($(stringify!($system_read)),*)?],
+                            writes: &[$($(stringify!($system_write)),*)?],
+                            enabled: self.[<system_ $system_name _enabled>]
+                        });
+                    )*
+                    stage += 1;
+                )*
+                systems.extend(self.runtime_systems.iter().map(|system| $crate::ECSSystemInfo {
+                    name: system.name,
+                    schedule: $crate::ECSSchedule::Update,
+                    stage,
+                    exclusive: false,
+                    reads: &[],
+                    writes: &[],
+                    enabled: system.enabled
+                }));
+                systems
+            }
+
+            /// Enables or disables the system of name, a disabled system
+            /// is skipped when its stage runs.
+            /// 
+            /// Fails with `ECSError::SystemNotFoundError` if no system is
+            /// declared with name.
+            fn set_system_enabled(&mut self, name: &str, enabled: bool) -> Result<(), $crate::ECSError> {
+                $($(
+                    if name == stringify!($system_name) {
+                        self.[<system_ $system_name _enabled>] = enabled;
+                        return Ok(());
+                    }
+                )*)*
+                let system = self.runtime_systems
+                    .iter_mut()
+                    .find(|system| system.name == name)
+                    .ok_or($crate::ECSError::SystemNotFoundError)?;
+                system.enabled = enabled;
+                Ok(())
+            }
         } // impl $ecs end
+
+        // Asserting at compile time that Ring Resources can hold a value,
+        // as pushing to an empty ring would never make room
+        $(
+            const _: () = assert!(
+                $ring_resource_len > 0,
+                concat!("Ring Resource `", stringify!($ring_resource_name), "` must have a capacity above 0.")
+            );
+        )*
+
+        // Asserting at compile time that the ECS is `Send` and `Sync`
+        // whenever its resource, event and component types are
+        const _: () = {
+            fn assert_send_sync<T: Send + Sync>() {}
+
+            #[allow(dead_code)]
+            fn [<assert_ $name:snake _send_sync>]()
+            where
+                $(for<'a> $resource_ty: Send + Sync,)*
+                $(for<'a> $arr_resource_ty: Send + Sync,)*
+                $(
+                    for<'a> $map_resource_key_type: Send + Sync,
+                    for<'a> $map_resource_value_type: Send + Sync,
+                )*
+                $(for<'a> $opt_resource_ty: Send + Sync,)*
+                $(for<'a> $vec_resource_ty: Send + Sync,)*
+                $(for<'a> $ring_resource_ty: Send + Sync,)*
+                $(for<'a> $lock_resource_ty: Send + Sync,)*
+                $(for<'a> $event_ty: Send + Sync,)*
+                $($($(for<'a> $req_type: Send + Sync,)*)?)+
+                $($(for<'a> $comp_type: Send + Sync,)*)+
+                $($(for<'a> $locked_comp_type: Send + Sync,)*)*
+                $($($(for<'a> $system_local: Send + Sync,)?)*)*
+            {
+                assert_send_sync::<$name>();
+            }
+        };
+
+        // Implementing type keyed Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$resource_name:camel>]> for $name {
+                type Value = $resource_ty;
+
+                fn get(&self) -> &$resource_ty {
+                    self.[<get_resource_ $resource_name>]()
+                }
+
+                fn get_mut(&mut self) -> &mut $resource_ty {
+                    self.[<get_resource_ $resource_name _mut>]()
+                }
+
+                fn set(&mut self, $resource_name: $resource_ty) {
+                    self.[<write_resource_ $resource_name>]($resource_name);
+                }
+            }
+        )*
+
+        // Implementing type keyed Array Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$arr_resource_name:camel>]> for $name {
+                type Value = [$arr_resource_ty; $arr_resource_len];
+
+                fn get(&self) -> &[$arr_resource_ty; $arr_resource_len] {
+                    &self.[<resource_ $arr_resource_name>]
+                }
+
+                fn get_mut(&mut self) -> &mut [$arr_resource_ty; $arr_resource_len] {
+                    self.[<resource_ $arr_resource_name _changed>] = [self._next_change_tick(); $arr_resource_len];
+                    &mut self.[<resource_ $arr_resource_name>]
+                }
+
+                fn set(&mut self, $arr_resource_name: [$arr_resource_ty; $arr_resource_len]) {
+                    *$crate::ECSResource::<resource::[<$arr_resource_name:camel>]>::get_mut(self) = $arr_resource_name;
+                }
+            }
+        )*
+
+        // Implementing type keyed Map Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$map_resource_name:camel>]> for $name {
+                type Value = $crate::__create_ecs_map_type!(
+                    $($map_resource_kind)? <$map_resource_key_type, $map_resource_value_type>
+                );
+
+                fn get(&self) -> &Self::Value {
+                    &self.[<resource_ $map_resource_name>]
+                }
+
+                fn get_mut(&mut self) -> &mut Self::Value {
+                    // Values may be changed or removed, so every key is marked
+                    // as changed, keys inserted through the returned map are
+                    // only tracked by the change of the whole Map Resource
+                    let tick = self._next_change_tick();
+                    self.[<resource_ $map_resource_name _changed>] = tick;
+                    self.[<resource_ $map_resource_name _removed>] = tick;
+                    self.[<resource_ $map_resource_name _key_changed>].clear();
+                    for [<$map_resource_name _key>] in self.[<resource_ $map_resource_name>].keys() {
+                        self.[<resource_ $map_resource_name _key_changed>].insert(
+                            [<$map_resource_name _key>].clone(),
+                            tick
+                        );
+                    }
+                    &mut self.[<resource_ $map_resource_name>]
+                }
+
+                fn set(&mut self, $map_resource_name: Self::Value) {
+                    let map = $crate::ECSResource::<resource::[<$map_resource_name:camel>]>::get_mut(self);
+                    *map = $map_resource_name;
+                    let tick = self.change_tick();
+                    self.[<resource_ $map_resource_name _key_changed>].clear();
+                    for [<$map_resource_name _key>] in self.[<resource_ $map_resource_name>].keys() {
+                        self.[<resource_ $map_resource_name _key_changed>].insert(
+                            [<$map_resource_name _key>].clone(),
+                            tick
+                        );
+                    }
+                }
+            }
+        )*
+
+        // Implementing type keyed Optional Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$opt_resource_name:camel>]> for $name {
+                type Value = Option<$opt_resource_ty>;
+
+                fn get(&self) -> &Option<$opt_resource_ty> {
+                    &self.[<resource_ $opt_resource_name>]
+                }
+
+                fn get_mut(&mut self) -> &mut Option<$opt_resource_ty> {
+                    self.[<resource_ $opt_resource_name _changed>] = self._next_change_tick();
+                    &mut self.[<resource_ $opt_resource_name>]
+                }
+
+                fn set(&mut self, $opt_resource_name: Option<$opt_resource_ty>) {
+                    *$crate::ECSResource::<resource::[<$opt_resource_name:camel>]>::get_mut(self) = $opt_resource_name;
+                }
+            }
+        )*
+
+        // Implementing type keyed Vec Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$vec_resource_name:camel>]> for $name {
+                type Value = Vec<$vec_resource_ty>;
+
+                fn get(&self) -> &Vec<$vec_resource_ty> {
+                    &self.[<resource_ $vec_resource_name>]
+                }
+
+                fn get_mut(&mut self) -> &mut Vec<$vec_resource_ty> {
+                    self.[<resource_ $vec_resource_name _changed>] = self._next_change_tick();
+                    &mut self.[<resource_ $vec_resource_name>]
+                }
+
+                fn set(&mut self, $vec_resource_name: Vec<$vec_resource_ty>) {
+                    *$crate::ECSResource::<resource::[<$vec_resource_name:camel>]>::get_mut(self) = $vec_resource_name;
+                }
+            }
+        )*
+
+        // Implementing type keyed Ring Resource access
+        $(
+            impl $crate::ECSResource<resource::[<$ring_resource_name:camel>]> for $name {
+                type Value = std::collections::VecDeque<$ring_resource_ty>;
+
+                fn get(&self) -> &std::collections::VecDeque<$ring_resource_ty> {
+                    &self.[<resource_ $ring_resource_name>]
+                }
+
+                /// Values pushed past the capacity of Ring Resource are
+                /// only removed by the next push
+                fn get_mut(&mut self) -> &mut std::collections::VecDeque<$ring_resource_ty> {
+                    self.[<resource_ $ring_resource_name _changed>] = self._next_change_tick();
+                    &mut self.[<resource_ $ring_resource_name>]
+                }
+
+                /// Keeps only the newest values that fit in Ring Resource
+                fn set(&mut self, $ring_resource_name: std::collections::VecDeque<$ring_resource_ty>) {
+                    let ring = $crate::ECSResource::<resource::[<$ring_resource_name:camel>]>::get_mut(self);
+                    *ring = $ring_resource_name;
+                    while ring.len() > $ring_resource_len {
+                        ring.pop_front();
+                    }
+                }
+            }
+        )*
+        // Implementing marker keyed Event access
+        $(
+            impl $crate::ECSEvent<event::[<$event_name:camel>]> for $name {
+                type Value = $event_ty;
+
+                fn events(&self) -> &$crate::ECSEvents<$event_ty> {
+                    &self.[<event_ $event_name>]
+                }
+            }
+        )*
+
+        // Implementing marker keyed Archtype access
+        $(
+            impl $crate::ECSArchetype<archetype::[<$entity_name:camel>]> for $name {
+                type Id = id::[<$entity_name:camel EntityId>];
+                type View<'a> = entity::[<$entity_name:camel EntityView>]<'a>;
+
+                fn ids(&self) -> impl Iterator<Item = &id::[<$entity_name:camel EntityId>]> {
+                    self.$entity_name.iter().map(|(_, ent)| ent).filter(|ent| ent.is_valid())
+                }
+
+                fn view(
+                    &self,
+                    [<$entity_name _id>]: &id::[<$entity_name:camel EntityId>]
+                ) -> Option<entity::[<$entity_name:camel EntityView>]<'_>> {
+                    self.[<get_ $entity_name>]([<$entity_name _id>])
+                }
+            }
+        )+
+
+        // Implementing Commands as a parameter of runtime systems,
+        // applied after the system ran
+        impl<'a> $crate::ECSSystemParam<$name> for &'a mut command::Commands {
+            type State = command::Commands;
+            type Item<'w> = &'w mut command::Commands;
+
+            fn init(ecs: &$name) -> command::Commands {
+                ecs.commands()
+            }
+
+            fn fetch<'w>(_ecs: &'w $name, state: &'w mut command::Commands) -> &'w mut command::Commands {
+                state
+            }
+
+            fn apply(ecs: &mut $name, state: &mut command::Commands) -> Result<(), $crate::ECSError> {
+                let commands = std::mem::replace(state, ecs.commands());
+                ecs.apply_commands(commands)
+            }
+
+            fn discard(ecs: &$name, state: &mut command::Commands) {
+                // Dropped commands release the Ids of their spawns
+                *state = ecs.commands();
+            }
+        }
         } // paste! end
     };
 } // macro_rules end
\ No newline at end of file
@@ -0,0 +1,195 @@
+use qoecs::*;
+
+#[global_allocator]
+static ALLOCATOR: ECSCountingAllocator = ECSCountingAllocator;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct PositionComponent {
+    x: f64,
+    y: f64
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct VoiceComponent {
+    gain: f32
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct NoteEvent {
+    pitch: u8
+}
+
+create_ecs!(
+    RealTimeECS,
+    Resources(frame => u64),
+    ArrayResources(),
+    MapResources(),
+    OptionalResources(),
+    VecResources(),
+    RingResources(),
+    LockedResources(),
+    Events(note => crate::NoteEvent),
+    Archtypes(
+        Entity(
+            voice,
+            fixed_capacity = 8,
+            Required(
+                position => crate::PositionComponent
+            ),
+            Components(
+                dense_voice => @dense crate::VoiceComponent,
+                sparse_voice => crate::VoiceComponent
+            )
+        ),
+        Entity(
+            grain,
+            capacity = 32,
+            Required(
+                position => crate::PositionComponent
+            ),
+            Components(
+                dense_voice => @dense crate::VoiceComponent,
+                sparse_voice => crate::VoiceComponent,
+                map_voice => @map crate::VoiceComponent
+            )
+        )
+    ),
+    LockedArchtypes(),
+    Systems()
+);
+
+fn voice(i: usize) -> entity::VoiceEntity {
+    entity::VoiceEntity {
+        position: PositionComponent { x: i as f64, y: 0. },
+        dense_voice: Some(VoiceComponent { gain: i as f32 }),
+        sparse_voice: Some(VoiceComponent { gain: i as f32 })
+    }
+}
+
+fn grain(i: usize) -> entity::GrainEntity {
+    entity::GrainEntity {
+        position: PositionComponent { x: i as f64, y: 0. },
+        dense_voice: Some(VoiceComponent { gain: i as f32 }),
+        sparse_voice: Some(VoiceComponent { gain: i as f32 }),
+        map_voice: Some(VoiceComponent { gain: i as f32 })
+    }
+}
+
+#[test]
+fn capacity_test() -> Result<(), ECSError> {
+    let mut ecs = RealTimeECS::new();
+    let mut ids = ECSCountingAllocator::assert_no_alloc(|| {
+        (0..32).map(|i| ecs.spawn_grain(grain(i)).map(|id| *id.get_id())).sum::<Result<usize, _>>()
+    })?;
+
+    ecs.reserve_grain(32);
+    ids += ECSCountingAllocator::assert_no_alloc(|| {
+        (32..64).map(|i| ecs.spawn_grain(grain(i)).map(|id| *id.get_id())).sum::<Result<usize, _>>()
+    })?;
+    assert_eq!(ids, (0..64).sum());
+
+    ecs.reserve_grain(32);
+    ECSCountingAllocator::assert_no_alloc(|| ecs.spawn_grain(grain(64)))?;
+    // Shrinking releases the reserved room, so the next spawn allocates
+    ecs.shrink_grain();
+    let before = ECSCountingAllocator::allocations();
+    ecs.spawn_grain(grain(65))?;
+    assert!(ECSCountingAllocator::allocations() > before);
+    Ok(())
+}
+
+#[test]
+fn archetype_full_test() -> Result<(), ECSError> {
+    let mut ecs = RealTimeECS::new();
+    let ids = (0..8).map(|i| ecs.spawn_voice(voice(i))).collect::<Result<Vec<_>, _>>()?;
+    assert!(matches!(ecs.spawn_voice(voice(8)), Err(ECSError::ArchetypeFull)));
+    assert!(matches!(
+        ecs.create_voice(id::VoiceEntityId::new(8), voice(8), ECSEntityCreateConflictResolution::Error),
+        Err(ECSError::ArchetypeFull)
+    ));
+
+    ecs.delete_voice(&ids[3]);
+    let reused = ecs.spawn_voice(voice(9))?;
+    assert_eq!(reused.get_id(), ids[3].get_id());
+    assert!(!ecs.has_voice(&ids[3]));
+    assert_eq!(ecs.get_position_of_voice(&reused), Some(&PositionComponent { x: 9., y: 0. }));
+
+    let mut commands = ecs.commands();
+    commands.spawn_voice(voice(10));
+    assert!(matches!(ecs.apply_commands(commands), Err(ECSError::ArchetypeFull)));
+    Ok(())
+}
+
+#[test]
+fn fixed_capacity_id_reuse_test() -> Result<(), ECSError> {
+    let mut ecs = RealTimeECS::new();
+    let created = ecs.create_voice(
+        id::VoiceEntityId::new(3),
+        voice(3),
+        ECSEntityCreateConflictResolution::Error
+    )?;
+    // Ids reserved by Commands that are dropped are released
+    let mut commands = ecs.commands();
+    for i in 0..4 {
+        commands.spawn_voice(voice(i));
+    }
+    drop(commands);
+
+    let ids = (0..7).map(|i| ecs.spawn_voice(voice(i))).collect::<Result<Vec<_>, _>>()?;
+    assert!(ids.iter().all(|id| id.get_id() != created.get_id()));
+    assert!(matches!(ecs.spawn_voice(voice(8)), Err(ECSError::ArchetypeFull)));
+
+    // The Id of the spawn after the failing one is released
+    let mut commands = ecs.commands();
+    commands.spawn_voice(voice(9));
+    ecs.delete_voice(&ids[0]);
+    commands.spawn_voice(voice(10));
+    assert!(matches!(ecs.apply_commands(commands), Err(ECSError::ArchetypeFull)));
+
+    // Shrinking keeps the room of fixed capacity archetypes
+    ecs.shrink_voice();
+    let reused = ECSCountingAllocator::assert_no_alloc(|| ecs.spawn_voice(voice(11)))?;
+    assert_eq!(reused.get_id(), ids[0].get_id());
+    assert!(matches!(ecs.spawn_voice(voice(12)), Err(ECSError::ArchetypeFull)));
+    Ok(())
+}
+
+#[test]
+fn no_alloc_after_warm_up_test() -> Result<(), ECSError> {
+    let mut ecs = RealTimeECS::new();
+    let mut reader = ecs.reader_note();
+    // Warm up the event buffers
+    for _ in 0..2 {
+        for pitch in 0..16 {
+            ecs.send_note(NoteEvent { pitch });
+        }
+        ecs.update_events();
+    }
+    ecs.read_note(&mut reader).count();
+
+    ECSCountingAllocator::assert_no_alloc(|| -> Result<(), ECSError> {
+        for frame in 0..100 {
+            let ids = [ecs.spawn_voice(voice(0))?, ecs.spawn_voice(voice(1))?, ecs.spawn_voice(voice(2))?];
+            ecs.add_position_to_voice(&ids[1], PositionComponent { x: 1., y: 1. });
+            ecs.remove_sparse_voice_from_voice(&ids[0]);
+            ecs.add_sparse_voice_to_voice(&ids[2], VoiceComponent { gain: 0.5 });
+            assert!(ecs.get_voice(&ids[1]).is_some());
+            for pitch in 0..16 {
+                ecs.send_note(NoteEvent { pitch });
+            }
+            assert_eq!(ecs.read_note(&mut reader).count(), 16);
+            ecs.update_events();
+            ecs.write_resource_frame(frame);
+            for id in &ids {
+                ecs.delete_voice(id);
+            }
+        }
+        Ok(())
+    })
+}
+
+#[test]
+#[should_panic(expected = "Allocated 1 times.")]
+fn assert_no_alloc_test() {
+    ECSCountingAllocator::assert_no_alloc(|| vec![1u8]);
+}
@@ -1,4 +1,6 @@
 use qoecs::*;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
 
 #[derive(PartialEq, Clone, Debug)]
 pub struct PositionComponent {
@@ -21,23 +23,51 @@ pub struct ArmorComponent {
     defense: i32
 }
 
+#[derive(PartialEq, Clone, Debug)]
+pub struct LifetimeComponent {
+    frames: u32
+}
+
 #[derive(PartialEq, Clone, Debug)]
 pub struct UseComponent {
     health: i32
 }
 
+static ARMOR_HOOK_TOTAL: AtomicI32 = AtomicI32::new(0);
+
+fn on_armor_add(_id: &id::ItemEntityId, armor: &ArmorComponent) {
+    ARMOR_HOOK_TOTAL.fetch_add(armor.defense, Ordering::SeqCst);
+}
+
+fn on_armor_replace(_id: &id::ItemEntityId, armor: &ArmorComponent) {
+    ARMOR_HOOK_TOTAL.fetch_add(100 * armor.defense, Ordering::SeqCst);
+}
+
+fn on_armor_remove(_id: &id::ItemEntityId, armor: &ArmorComponent) {
+    ARMOR_HOOK_TOTAL.fetch_sub(armor.defense, Ordering::SeqCst);
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct DamageEvent {
+    amount: i32
+}
+
 create_ecs!(
     TestECS,
     Resources(time => u64, step => u64),
-    ArrayResources(seq => [u64; 10], offset => [u64; 10]),
-    MapResources(name_conv => <u64, String>, text_conv => <u64, String>),
+    ArrayResources(seq => [u64; 10], offset => [u64; 10], names => [String; 4]),
+    MapResources(name_conv => <u64, String>, text_conv => <u64, String> @ordered),
     OptionalResources(gravity => f32),
+    VecResources(spawn_queue => u64),
+    RingResources(frame_times => [u64; 3]),
+    LockedResources(score => u64 = 0, status => String),
+    Events(damage => crate::DamageEvent, heal => crate::DamageEvent),
     Archtypes(
         Entity(
             mob,
             Components(
-                position => crate::PositionComponent,
-                clone_position => crate::PositionComponent,
+                position => @dense crate::PositionComponent,
+                clone_position => @map crate::PositionComponent,
                 velocity => crate::VelocityComponent
             )
         ),
@@ -45,11 +75,37 @@ create_ecs!(
             item,
             Components(
                 weapon => crate::WeaponComponent,
-                armor => crate::ArmorComponent,
+                armor => @sparse crate::ArmorComponent {
+                    on_add: crate::on_armor_add,
+                    on_replace: crate::on_armor_replace,
+                    on_remove: crate::on_armor_remove
+                },
                 useable => crate::UseComponent
             )
+        ),
+        Entity(
+            particle,
+            capacity = 64,
+            Required(
+                position => crate::PositionComponent,
+                velocity => crate::VelocityComponent
+            ),
+            Components(
+                lifetime => crate::LifetimeComponent
+            )
+        )
+    ),
+    LockedArchtypes(
+        Entity(
+            unit,
+            capacity = 16,
+            Components(
+                position => crate::PositionComponent,
+                velocity => crate::VelocityComponent
+            )
         )
-    )
+    ),
+    Systems()
 );
 
 #[test]
@@ -64,6 +120,76 @@ fn required_resource_test() {
     assert_eq!(ecs.get_resource_time(), &200);
 }
 
+#[test]
+fn resource_scope_test() {
+    let mut ecs = TestECS::new();
+    ecs.write_resource_time(5);
+    let mob_id = ecs.resource_scope_time(|ecs, time| {
+        *time += 1;
+        *ecs.get_resource_step_mut() = *time;
+        ecs.create_mob(
+            id::MobEntityId::new(0),
+            entity::MobEntity { position: None, clone_position: None, velocity: None },
+            ECSEntityCreateConflictResolution::Error
+        ).unwrap()
+    });
+    assert!(ecs.has_mob(&mob_id));
+    assert_eq!(ecs.get_resource_time(), &6);
+    assert_eq!(ecs.get_resource_step(), &6);
+}
+
+#[test]
+fn resource_scope_panic_test() {
+    let mut ecs = TestECS::new();
+    let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        ecs.resource_scope_time(|ecs, time| {
+            *time = 3;
+            ecs.get_resource_time();
+        })
+    })).unwrap_err();
+    assert_eq!(
+        panic.downcast_ref::<String>().map(String::as_str),
+        Some("Resource `time` is taken by `resource_scope_time`.")
+    );
+    assert_eq!(ecs.get_resource_time(), &3);
+}
+
+fn advance<W, M>(ecs: &mut W, by: u64)
+where
+    W: ECSResource<M, Value = u64>
+{
+    *ecs.get_mut() += by;
+}
+
+#[test]
+fn type_keyed_resource_test() {
+    let mut ecs = TestECS::new();
+    advance::<_, resource::Time>(&mut ecs, 2);
+    advance::<_, resource::Step>(&mut ecs, 3);
+    advance::<_, resource::Time>(&mut ecs, 4);
+    assert_eq!(ecs.get_resource_time(), &6);
+    assert_eq!(ecs.get_resource_step(), &3);
+    assert_eq!(ECSResource::<resource::Step>::get(&ecs), &3);
+
+    let tick = ecs.change_tick();
+    ECSResource::<resource::Gravity>::set(&mut ecs, Some(1.));
+    assert_eq!(ecs.get_resource_gravity(), Some(&1.));
+    assert!(ecs.resource_gravity_changed_since(tick));
+
+    ECSResource::<resource::NameConv>::get_mut(&mut ecs).insert(1, "A".to_owned());
+    assert_eq!(ecs.get_resource_name_conv(1), Some(&"A".to_owned()));
+    assert!(ecs.resource_name_conv_changed_since(tick));
+
+    ECSResource::<resource::Seq>::set(&mut ecs, [2; 10]);
+    assert_eq!(ecs.get_resource_seq(9).unwrap(), &2);
+
+    ECSResource::<resource::SpawnQueue>::set(&mut ecs, vec![1, 2]);
+    assert_eq!(ecs.len_resource_spawn_queue(), 2);
+
+    ECSResource::<resource::FrameTimes>::set(&mut ecs, (0..5).collect());
+    assert_eq!(ecs.iter_resource_frame_times().collect::<Vec<_>>(), vec![&2, &3, &4]);
+}
+
 #[test]
 fn array_resource_test() -> Result<(), ECSError> {
     let mut ecs = TestECS::new();
@@ -91,6 +217,38 @@ fn array_resource_test() -> Result<(), ECSError> {
     Ok(())
 }
 
+#[test]
+fn non_copy_array_resource_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    assert!(ecs.iter_resource_names().all(|name| name.is_empty()));
+
+    ecs.write_resource_names("A".to_owned(), 0)?;
+    ecs.write_resource_names("B".to_owned(), 3)?;
+    ecs.swap_resource_names(0, 1)?;
+    assert_eq!(ecs.get_resource_names_slice(), &["", "A", "", "B"]);
+    match ecs.swap_resource_names(0, 4) {
+        Err(ECSError::ArrayResourceWriteOutOfBoundsError) => (),
+        _ => panic!("Should return Out of Bound Error")
+    };
+
+    assert_eq!(ecs.take_resource_names(3)?, "B".to_owned());
+    assert_eq!(ecs.get_resource_names(3)?, "");
+    assert!(ecs.take_resource_names(4).is_err());
+
+    for name in ecs.iter_resource_names_mut() {
+        name.push('C');
+    }
+    ecs.get_resource_names_slice_mut()[0].push('D');
+    assert_eq!(
+        ecs.iter_resource_names().cloned().collect::<Vec<_>>(),
+        vec!["CD".to_owned(), "AC".to_owned(), "C".to_owned(), "C".to_owned()]
+    );
+
+    ecs.clear_resource_names();
+    assert!(ecs.iter_resource_names().all(|name| name.is_empty()));
+    Ok(())
+}
+
 #[test]
 fn map_resource_test() -> Result<(), ECSError> {
     let mut ecs = TestECS::new();
@@ -121,6 +279,46 @@ fn map_resource_test() -> Result<(), ECSError> {
     Ok(())
 }
 
+#[test]
+fn map_resource_api_test() {
+    let mut ecs = TestECS::new();
+    for i in [5, 1, 3, 2, 4] {
+        ecs.write_resource_text_conv(i, i.to_string());
+        ecs.write_resource_name_conv(i, i.to_string());
+    }
+    assert_eq!(ecs.len_resource_text_conv(), 5);
+    assert!(ecs.contains_resource_text_conv(3));
+    assert!(!ecs.contains_resource_text_conv(6));
+    assert_eq!(
+        ecs.iter_resource_text_conv().map(|(k, _)| *k).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5]
+    );
+
+    assert_eq!(ecs.remove_resource_text_conv(3), Some("3".to_owned()));
+    assert_eq!(ecs.remove_resource_text_conv(3), None);
+    assert_eq!(ecs.remove_resource_name_conv(3), Some("3".to_owned()));
+    assert!(!ecs.contains_resource_name_conv(3));
+
+    ecs.retain_resource_text_conv(|k, _| k % 2 == 1);
+    ecs.retain_resource_name_conv(|k, _| k % 2 == 1);
+    assert_eq!(ecs.len_resource_text_conv(), 2);
+    assert_eq!(ecs.len_resource_name_conv(), 2);
+
+    for (_, v) in ecs.iter_resource_text_conv_mut() {
+        v.push('!');
+    }
+    ecs.entry_resource_text_conv(5, |entry| entry.or_default().push('?'));
+    assert_eq!(ecs.entry_resource_text_conv(7, |entry| entry.or_insert_with(|| "7".to_owned()).len()), 1);
+    ecs.entry_resource_name_conv(1, |entry| *entry.or_default() += "?");
+    assert_eq!(
+        ecs.iter_resource_text_conv().collect::<Vec<_>>(),
+        vec![(&1, &"1!".to_owned()), (&5, &"5!?".to_owned()), (&7, &"7".to_owned())]
+    );
+    let mut names = ecs.iter_resource_name_conv().collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec![(&1, &"1?".to_owned()), (&5, &"5".to_owned())]);
+}
+
 #[test]
 fn array_resource_outofbound_test() {
     let mut ecs = TestECS::new();
@@ -152,6 +350,627 @@ fn optional_resource_test() {
 }
 
 #[test]
+fn vec_resource_test() {
+    let mut ecs = TestECS::new();
+    assert_eq!(ecs.len_resource_spawn_queue(), 0);
+    assert_eq!(ecs.pop_resource_spawn_queue(), None);
+
+    ecs.push_resource_spawn_queue(1);
+    ecs.extend_resource_spawn_queue([2, 3, 4]);
+    assert_eq!(ecs.len_resource_spawn_queue(), 4);
+    assert_eq!(ecs.get_resource_spawn_queue(1), Some(&2));
+    assert_eq!(ecs.get_resource_spawn_queue(4), None);
+    assert_eq!(ecs.pop_resource_spawn_queue(), Some(4));
+
+    for v in ecs.iter_resource_spawn_queue_mut() {
+        *v *= 10;
+    }
+    assert_eq!(ecs.iter_resource_spawn_queue().collect::<Vec<_>>(), vec![&10, &20, &30]);
+
+    ecs.clear_resource_spawn_queue();
+    assert_eq!(ecs.len_resource_spawn_queue(), 0);
+}
+
+#[test]
+fn ring_resource_test() {
+    let mut ecs = TestECS::new();
+    assert_eq!(ecs.get_resource_frame_times_newest(), None);
+
+    assert_eq!(ecs.push_resource_frame_times(1), None);
+    assert_eq!(ecs.push_resource_frame_times(2), None);
+    assert_eq!(ecs.push_resource_frame_times(3), None);
+    assert_eq!(ecs.push_resource_frame_times(4), Some(1));
+    assert_eq!(ecs.push_resource_frame_times(5), Some(2));
+    assert_eq!(ecs.len_resource_frame_times(), 3);
+    assert_eq!(ecs.get_resource_frame_times(0), Some(&3));
+    assert_eq!(ecs.get_resource_frame_times_newest(), Some(&5));
+    assert_eq!(ecs.iter_resource_frame_times().collect::<Vec<_>>(), vec![&3, &4, &5]);
+
+    for v in ecs.iter_resource_frame_times_mut() {
+        *v += 1;
+    }
+    assert_eq!(ecs.iter_resource_frame_times().collect::<Vec<_>>(), vec![&4, &5, &6]);
+
+    ecs.clear_resource_frame_times();
+    assert_eq!(ecs.len_resource_frame_times(), 0);
+}
+
+#[test]
+fn event_test() {
+    let mut ecs = TestECS::new();
+    let mut reader = ecs.reader_damage();
+    assert_eq!(ecs.read_damage(&mut reader).count(), 0);
+
+    ecs.send_damage(DamageEvent { amount: 1 });
+    ecs.send_damage(DamageEvent { amount: 2 });
+    let late_reader = ecs.reader_damage();
+    assert_eq!(
+        ecs.read_damage(&mut reader).collect::<Vec<_>>(),
+        vec![&DamageEvent { amount: 1 }, &DamageEvent { amount: 2 }]
+    );
+    assert_eq!(ecs.read_damage(&mut reader).count(), 0);
+
+    ecs.update_events();
+    ecs.send_damage(DamageEvent { amount: 3 });
+    assert_eq!(
+        ecs.read_damage(&mut reader).collect::<Vec<_>>(),
+        vec![&DamageEvent { amount: 3 }]
+    );
+
+    let mut late_reader = late_reader;
+    ecs.update_events();
+    assert_eq!(
+        ecs.read_damage(&mut late_reader).collect::<Vec<_>>(),
+        vec![&DamageEvent { amount: 3 }]
+    );
+
+    ecs.update_events();
+    let mut new_reader = ecs.reader_damage();
+    assert_eq!(ecs.read_damage(&mut new_reader).count(), 0);
+    assert_eq!(ecs.read_heal(&mut ecs.reader_heal()).count(), 0);
+}
+
+#[test]
+fn event_clear_test() {
+    let mut ecs = TestECS::new();
+    let mut reader = ecs.reader_heal();
+    ecs.send_heal(DamageEvent { amount: 1 });
+    ecs.clear_events_heal();
+    assert_eq!(ecs.read_heal(&mut reader).count(), 0);
+
+    ecs.send_heal(DamageEvent { amount: 2 });
+    assert_eq!(
+        ecs.read_heal(&mut reader).collect::<Vec<_>>(),
+        vec![&DamageEvent { amount: 2 }]
+    );
+}
+
+#[test]
+fn mutable_resource_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    *ecs.get_resource_time_mut() += 5;
+    ecs.update_resource_time(|time| *time *= 3);
+    assert_eq!(ecs.get_resource_time(), &15);
+
+    *ecs.get_resource_seq_mut(2)? = 7;
+    ecs.update_resource_seq(2, |seq| *seq += 1)?;
+    assert_eq!(ecs.get_resource_seq(2)?, &8);
+    match ecs.update_resource_seq(10, |seq| *seq += 1) {
+        Err(ECSError::ArrayResourceWriteOutOfBoundsError) => (),
+        _ => panic!("Should return Out of Bound Error")
+    };
+
+    assert_eq!(ecs.get_resource_name_conv_mut(0), None);
+    assert!(!ecs.update_resource_name_conv(0, |name| name.push('B')));
+    ecs.write_resource_name_conv(0, "A".to_owned());
+    ecs.get_resource_name_conv_mut(0).unwrap().push('B');
+    assert!(ecs.update_resource_name_conv(0, |name| name.push('C')));
+    assert_eq!(ecs.get_resource_name_conv(0), Some(&"ABC".to_owned()));
+
+    assert_eq!(ecs.get_resource_gravity_mut(), None);
+    assert!(!ecs.update_resource_gravity(|gravity| *gravity = 1.));
+    ecs.write_resource_gravity(1.);
+    *ecs.get_resource_gravity_mut().unwrap() += 1.;
+    assert!(ecs.update_resource_gravity(|gravity| *gravity *= 2.));
+    assert_eq!(ecs.get_resource_gravity(), Some(&4.));
+    Ok(())
+}
+
+#[test]
+fn resource_change_tracking_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let tick = ecs.change_tick();
+    assert!(!ecs.resource_time_changed_since(tick));
+    assert!(!ecs.resource_seq_changed_since(tick));
+    assert!(!ecs.resource_name_conv_changed_since(tick));
+    assert!(!ecs.resource_gravity_changed_since(tick));
+    assert!(!ecs.resource_spawn_queue_changed_since(tick));
+    assert!(!ecs.resource_frame_times_changed_since(tick));
+
+    ecs.write_resource_time(1);
+    ecs.update_resource_seq(3, |seq| *seq += 1)?;
+    ecs.write_resource_name_conv(1, "A".to_owned());
+    ecs.write_resource_gravity(1.);
+    ecs.push_resource_spawn_queue(1);
+    ecs.push_resource_frame_times(1);
+    assert!(ecs.resource_time_changed_since(tick));
+    assert!(!ecs.resource_step_changed_since(tick));
+    assert!(ecs.resource_seq_changed_since(tick));
+    assert!(ecs.resource_seq_slot_changed_since(3, tick)?);
+    assert!(!ecs.resource_seq_slot_changed_since(2, tick)?);
+    assert!(ecs.resource_seq_slot_changed_since(10, tick).is_err());
+    assert!(ecs.resource_name_conv_changed_since(tick));
+    assert!(ecs.resource_name_conv_key_changed_since(1, tick));
+    assert!(!ecs.resource_name_conv_key_changed_since(2, tick));
+    assert!(ecs.resource_gravity_changed_since(tick));
+    assert!(ecs.resource_spawn_queue_changed_since(tick));
+    assert!(ecs.resource_frame_times_changed_since(tick));
+
+    let tick = ecs.change_tick();
+    assert!(!ecs.resource_time_changed_since(tick));
+    assert!(!ecs.resource_name_conv_key_changed_since(1, tick));
+    assert!(ecs.get_resource_name_conv_mut(2).is_none());
+    assert!(!ecs.resource_name_conv_key_changed_since(2, tick));
+    assert!(ecs.get_resource_gravity_mut().is_some());
+    assert!(ecs.resource_gravity_changed_since(tick));
+
+    let tick = ecs.change_tick();
+    ecs.retain_resource_name_conv(|_, _| false);
+    assert!(ecs.resource_name_conv_key_changed_since(1, tick));
+
+    ecs.write_resource_name_conv(2, "B".to_owned());
+    ecs.write_resource_name_conv(3, "C".to_owned());
+    let tick = ecs.change_tick();
+    assert!(!ecs.resource_name_conv_key_changed_since(1, tick));
+    assert_eq!(ecs.remove_resource_name_conv(2), Some("B".to_owned()));
+    assert!(ecs.resource_name_conv_key_changed_since(2, tick));
+    assert!(!ecs.resource_name_conv_key_changed_since(3, tick));
+    let tick = ecs.change_tick();
+    ecs.entry_resource_name_conv(4, |entry| {
+        entry.and_modify(|name| name.push('!'));
+    });
+    assert!(!ecs.resource_name_conv_key_changed_since(4, tick));
+    assert!(!ecs.resource_name_conv_changed_since(tick));
+    ecs.entry_resource_name_conv(3, |entry| {
+        entry.and_modify(|name| name.push('!'));
+    });
+    assert!(ecs.resource_name_conv_key_changed_since(3, tick));
+    let tick = ecs.change_tick();
+    ecs.clear_resource_name_conv();
+    assert!(ecs.resource_name_conv_key_changed_since(3, tick));
+    let tick = ecs.change_tick();
+    assert!(!ecs.resource_name_conv_key_changed_since(3, tick));
+    ecs.clear_resource_gravity();
+    assert!(ecs.resource_gravity_changed_since(tick));
+    assert!(!ecs.resource_seq_changed_since(tick));
+    ecs.clear_resource_seq();
+    assert!(ecs.resource_seq_slot_changed_since(0, tick)?);
+    Ok(())
+}
+
+#[test]
+fn delete_entity_test() {
+    let mut ecs = TestECS::new();
+    let item_id = ecs.create_item(
+        id::ItemEntityId::new(3),
+        entity::ItemEntity {
+            weapon: Some(WeaponComponent { attack: 5 }),
+            armor: None,
+            useable: Some(UseComponent { health: 10 })
+        },
+        ECSEntityCreateConflictResolution::Error
+    ).unwrap();
+
+    assert_eq!(ecs.remove_useable_from_item(&item_id), Some(UseComponent { health: 10 }));
+    assert_eq!(ecs.remove_useable_from_item(&item_id), None);
+    assert_eq!(ecs.get_useable_of_item(&item_id), None);
+
+    let item = ecs.delete_item(&item_id).unwrap();
+    assert_eq!(item.weapon, Some(WeaponComponent { attack: 5 }));
+    assert_eq!(item.armor, None);
+    assert!(!ecs.has_item(&item_id));
+    assert_eq!(ecs.get_weapon_of_item(&item_id), None);
+    assert!(ecs.delete_item(&item_id).is_none());
+
+    let new_item_id = ecs.create_item(
+        id::ItemEntityId::new(3),
+        entity::ItemEntity { weapon: None, armor: None, useable: None },
+        ECSEntityCreateConflictResolution::Error
+    ).unwrap();
+    assert!(ecs.has_item(&new_item_id));
+    assert!(!ecs.has_item(&item_id));
+
+    // Stale ids do not reach the entity that reuses their slot
+    ecs.add_weapon_to_item(&new_item_id, WeaponComponent { attack: 7 });
+    assert!(ecs.get_item(&item_id).is_none());
+    assert_eq!(ecs.get_weapon_of_item(&item_id), None);
+    ecs.add_weapon_to_item(&item_id, WeaponComponent { attack: 1 });
+    assert_eq!(ecs.remove_weapon_from_item(&item_id), None);
+    assert_eq!(ecs.get_weapon_of_item(&new_item_id), Some(&WeaponComponent { attack: 7 }));
+}
+
+#[test]
+fn spawn_command_id_in_use_test() {
+    let mut ecs = TestECS::new();
+    let mut commands = ecs.commands();
+    let reserved = commands.spawn_mob(entity::MobEntity {
+        position: Some(PositionComponent { x: 5., y: 0. }),
+        clone_position: None,
+        velocity: None
+    });
+    let created = ecs.create_mob(
+        reserved.clone(),
+        entity::MobEntity { position: None, clone_position: None, velocity: None },
+        ECSEntityCreateConflictResolution::Error
+    ).unwrap();
+
+    assert!(matches!(ecs.apply_commands(commands), Err(ECSError::EntityIdInUseError)));
+    assert_eq!(ecs.get_position_of_mob(&created), None);
+}
+
+#[test]
+fn entity_id_out_of_range_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let mob = || entity::MobEntity {
+        position: Some(PositionComponent { x: 1., y: 0. }),
+        clone_position: Some(PositionComponent { x: 0., y: 1. }),
+        velocity: Some(VelocityComponent { vector: (1., 0.) })
+    };
+    for raw in [usize::MAX, 1 << 40, ECS_MAX_ENTITY_ID + 1] {
+        assert!(matches!(
+            ecs.create_mob(id::MobEntityId::new(raw), mob(), ECSEntityCreateConflictResolution::Error),
+            Err(ECSError::EntityIdOutOfRangeError)
+        ));
+    }
+
+    let far = ecs.create_mob(id::MobEntityId::new(1 << 20), mob(), ECSEntityCreateConflictResolution::Error)?;
+    assert_eq!(ecs.get_velocity_of_mob(&far), Some(&VelocityComponent { vector: (1., 0.) }));
+    assert_eq!(ecs.get_position_of_mob(&far), Some(&PositionComponent { x: 1., y: 0. }));
+    Ok(())
+}
+
+#[test]
+fn entity_id_reuse_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let mob = |x: f64| entity::MobEntity {
+        position: Some(PositionComponent { x, y: 0. }),
+        clone_position: None,
+        velocity: None
+    };
+    let first = ecs.spawn_mob(mob(0.))?;
+    ecs.delete_mob(&first);
+    let second = ecs.spawn_mob(mob(1.))?;
+    assert_eq!(second.get_id(), first.get_id());
+    assert_eq!(*second.get_generation(), first.get_generation() + 1);
+    assert!(!ecs.has_mob(&first));
+    assert_eq!(ecs.get_position_of_mob(&first), None);
+    assert_eq!(ecs.get_position_of_mob(&second), Some(&PositionComponent { x: 1., y: 0. }));
+
+    ecs.delete_mob(&second);
+    let mut commands = ecs.commands();
+    let third = commands.spawn_mob(mob(2.));
+    ecs.apply_commands(commands)?;
+    assert_eq!(third.get_id(), first.get_id());
+    assert!(ecs.has_mob(&third));
+    assert!(!ecs.has_mob(&second));
+    assert_eq!(ecs.get_position_of_mob(&third), Some(&PositionComponent { x: 2., y: 0. }));
+    Ok(())
+}
+
+#[test]
+fn create_over_reused_id_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let mob = |x: f64| entity::MobEntity {
+        position: Some(PositionComponent { x, y: 0. }),
+        clone_position: None,
+        velocity: None
+    };
+    let stale = ecs.spawn_mob(mob(0.))?;
+    ecs.delete_mob(&stale);
+    let live = ecs.spawn_mob(mob(1.))?;
+    assert_eq!(live.get_id(), stale.get_id());
+
+    assert!(matches!(
+        ecs.create_mob(stale.clone(), mob(9.), ECSEntityCreateConflictResolution::Error),
+        Err(ECSError::EntityIdInUseError)
+    ));
+    assert_eq!(
+        ecs.create_mob(stale.clone(), mob(9.), ECSEntityCreateConflictResolution::Ignore)?,
+        live
+    );
+    assert_eq!(ecs.get_position_of_mob(&live), Some(&PositionComponent { x: 1., y: 0. }));
+    Ok(())
+}
+
+#[test]
+fn commands_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let existing_id = ecs.create_mob(
+        id::MobEntityId::new(0),
+        entity::MobEntity {
+            position: Some(PositionComponent { x: 0., y: 0. }),
+            clone_position: None,
+            velocity: None
+        },
+        ECSEntityCreateConflictResolution::Error
+    )?;
+
+    let mut commands = ecs.commands();
+    let spawned_id = commands.spawn_mob(
+        entity::MobEntity { position: None, clone_position: None, velocity: None }
+    );
+    assert_ne!(spawned_id.get_id(), existing_id.get_id());
+    assert!(!ecs.has_mob(&spawned_id));
+
+    commands.add_velocity_to_mob(&spawned_id, VelocityComponent { vector: (1., 1.) });
+    commands.remove_position_from_mob(&existing_id);
+    commands.write_resource_time(10);
+    commands.write_resource_name_conv(1, "A".to_owned());
+    commands.write_resource_gravity(9.8);
+    commands.push_resource_spawn_queue(1);
+    commands.push_resource_frame_times(2);
+    assert_eq!(commands.len(), 8);
+    assert_eq!(ecs.get_resource_time(), &0);
+
+    ecs.apply_commands(commands)?;
+    assert!(ecs.has_mob(&spawned_id));
+    assert_eq!(ecs.get_velocity_of_mob(&spawned_id), Some(&VelocityComponent { vector: (1., 1.) }));
+    assert_eq!(ecs.get_position_of_mob(&existing_id), None);
+    assert_eq!(ecs.get_resource_time(), &10);
+    assert_eq!(ecs.get_resource_name_conv(1), Some(&"A".to_owned()));
+    assert_eq!(ecs.get_resource_gravity(), Some(&9.8));
+    assert_eq!(ecs.get_resource_spawn_queue(0), Some(&1));
+    assert_eq!(ecs.get_resource_frame_times_newest(), Some(&2));
+
+    let mut commands = ecs.commands();
+    commands.delete_mob(&spawned_id);
+    commands.write_resource_seq(1, 42);
+    commands.write_resource_time(20);
+    match ecs.apply_commands(commands) {
+        Err(ECSError::ArrayResourceWriteOutOfBoundsError) => (),
+        _ => panic!("Should return Out of Bound Error")
+    };
+    assert!(!ecs.has_mob(&spawned_id));
+    assert_eq!(ecs.get_resource_time(), &10);
+    Ok(())
+}
+
+#[test]
+fn component_hook_test() {
+    let mut ecs = TestECS::new();
+    let item_id = ecs.create_item(
+        id::ItemEntityId::new(7),
+        entity::ItemEntity {
+            weapon: None,
+            armor: Some(ArmorComponent { defense: 3 }),
+            useable: None
+        },
+        ECSEntityCreateConflictResolution::Error
+    ).unwrap();
+    assert_eq!(ARMOR_HOOK_TOTAL.load(Ordering::SeqCst), 3);
+
+    ecs.add_armor_to_item(&item_id, ArmorComponent { defense: 2 });
+    assert_eq!(ARMOR_HOOK_TOTAL.load(Ordering::SeqCst), 203);
+
+    ecs.remove_armor_from_item(&item_id);
+    assert_eq!(ARMOR_HOOK_TOTAL.load(Ordering::SeqCst), 201);
+
+    ecs.add_armor_to_item(&item_id, ArmorComponent { defense: 1 });
+    assert_eq!(ARMOR_HOOK_TOTAL.load(Ordering::SeqCst), 202);
+    ecs.delete_item(&item_id);
+    assert_eq!(ARMOR_HOOK_TOTAL.load(Ordering::SeqCst), 201);
+}
+
+#[test]
+fn locked_resource_test() {
+    let mut ecs = TestECS::new();
+    let tick = ecs.change_tick();
+    assert_eq!(*ecs.read_resource_score(), 0);
+    assert_eq!(*ecs.read_resource_status(), "");
+    assert!(!ecs.resource_score_changed_since(tick));
+
+    std::thread::scope(|scope| {
+        let ecs = &ecs;
+        for _ in 0..4 {
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    *ecs.lock_resource_score() += 1;
+                }
+                ecs.write_resource_status("done".to_owned());
+            });
+        }
+    });
+    assert_eq!(*ecs.read_resource_score(), 400);
+    assert_eq!(*ecs.read_resource_status(), "done");
+    assert!(ecs.resource_score_changed_since(tick));
+    assert!(ecs.resource_status_changed_since(tick));
+
+    let tick = ecs.change_tick();
+    *ecs.get_resource_score_mut() = 1;
+    assert!(ecs.resource_score_changed_since(tick));
+    assert!(!ecs.resource_status_changed_since(tick));
+
+    let mut commands = ecs.commands();
+    commands.write_resource_score(7);
+    ecs.apply_commands(commands).unwrap();
+    assert_eq!(*ecs.read_resource_score(), 7);
+}
+
+#[test]
+fn locked_archetype_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let unit_id = ecs.spawn_unit(entity::UnitEntity {
+        position: Some(PositionComponent { x: 0.0, y: 0.0 }),
+        velocity: None
+    })?;
+    assert!(ecs.has_unit(&unit_id));
+    assert!(ecs.read_velocity_of_unit(&unit_id).is_none());
+
+    std::thread::scope(|scope| {
+        let ecs = &ecs;
+        let unit_id = &unit_id;
+        for _ in 0..4 {
+            scope.spawn(move || {
+                for _ in 0..100 {
+                    ecs.write_position_of_unit(unit_id).unwrap().x += 1.0;
+                }
+                // Spawning and deleting from several threads at once
+                let other_id = ecs.spawn_unit(entity::UnitEntity {
+                    position: None,
+                    velocity: Some(VelocityComponent { vector: (1.0, 1.0) })
+                }).unwrap();
+                assert_eq!(ecs.read_velocity_of_unit(&other_id).unwrap().vector, (1.0, 1.0));
+                assert!(ecs.delete_unit(&other_id).is_some());
+                assert!(ecs.delete_unit(&other_id).is_none());
+            });
+        }
+    });
+    assert_eq!(ecs.read_position_of_unit(&unit_id).unwrap().x, 400.0);
+
+    ecs.add_velocity_to_unit(&unit_id, VelocityComponent { vector: (0.0, 1.0) });
+    ecs.get_velocity_of_unit_mut(&unit_id).unwrap().vector.0 = 2.0;
+    assert_eq!(ecs.remove_velocity_from_unit(&unit_id), Some(VelocityComponent { vector: (2.0, 1.0) }));
+    assert!(ecs.remove_velocity_from_unit(&unit_id).is_none());
+
+    // The Id of a deleted entity does not reach the entity its id is reused for
+    let deleted = ecs.delete_unit(&unit_id).unwrap();
+    assert_eq!(deleted.position, Some(PositionComponent { x: 400.0, y: 0.0 }));
+    let reused_id = ecs.spawn_unit(entity::UnitEntity {
+        position: Some(PositionComponent { x: 1.0, y: 1.0 }),
+        velocity: None
+    })?;
+    assert_eq!(reused_id.get_id(), unit_id.get_id());
+    assert!(!ecs.has_unit(&unit_id));
+    assert!(ecs.read_position_of_unit(&unit_id).is_none());
+    ecs.add_velocity_to_unit(&unit_id, VelocityComponent { vector: (0.0, 1.0) });
+    assert!(ecs.read_velocity_of_unit(&reused_id).is_none());
+    assert_eq!(ecs.read_position_of_unit(&reused_id).unwrap().x, 1.0);
+    Ok(())
+}
+
+#[test]
+fn send_sync_test() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<TestECS>();
+    assert_send_sync::<command::Commands>();
+
+    let ecs = Arc::new(TestECS::new());
+    let reader = {
+        let ecs = ecs.clone();
+        std::thread::spawn(move || *ecs.read_resource_score())
+    };
+    assert_eq!(reader.join().unwrap(), 0);
+}
+
+#[test]
+fn observer_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let log = Arc::new(Mutex::new(vec![]));
+
+    let spawn_log = log.clone();
+    ecs.observe_mob(ECSEntityEvent::Spawned, move |mob_id, _| {
+        spawn_log.lock().unwrap().push(("spawned", *mob_id.get_id()));
+    });
+    let delete_log = log.clone();
+    ecs.observe_mob(ECSEntityEvent::Deleted, move |mob_id, _| {
+        delete_log.lock().unwrap().push(("deleted", *mob_id.get_id()));
+    });
+    let velocity_log = log.clone();
+    ecs.observe_velocity_of_mob(ECSComponentEvent::Removed, move |mob_id, _| {
+        velocity_log.lock().unwrap().push(("velocity removed", *mob_id.get_id()));
+    });
+    let position_log = log.clone();
+    ecs.observe_position_of_mob(ECSComponentEvent::Changed, move |mob_id, commands| {
+        position_log.lock().unwrap().push(("position changed", *mob_id.get_id()));
+        commands.remove_velocity_from_mob(mob_id);
+    });
+
+    let mob_id = ecs.create_mob(
+        id::MobEntityId::new(1),
+        entity::MobEntity {
+            position: Some(PositionComponent { x: 0., y: 0. }),
+            clone_position: None,
+            velocity: Some(VelocityComponent { vector: (1., 0.) })
+        },
+        ECSEntityCreateConflictResolution::Error
+    )?;
+    ecs.add_position_to_mob(&mob_id, PositionComponent { x: 1., y: 0. });
+    assert!(ecs.get_velocity_of_mob(&mob_id).is_some());
+
+    ecs.flush_commands()?;
+    assert_eq!(ecs.get_velocity_of_mob(&mob_id), None);
+    ecs.delete_mob(&mob_id);
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            ("spawned", 1),
+            ("position changed", 1),
+            ("velocity removed", 1),
+            ("deleted", 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn sparse_component_storage_test() {
+    let mut ecs = TestECS::new();
+    let mut commands = ecs.commands();
+    let ids = (0..5)
+        .map(|i| commands.spawn_mob(entity::MobEntity {
+            position: None,
+            clone_position: None,
+            velocity: Some(VelocityComponent { vector: (i as f64, 0.) })
+        }))
+        .collect::<Vec<_>>();
+    ecs.apply_commands(commands).unwrap();
+
+    assert_eq!(
+        ecs.remove_velocity_from_mob(&ids[1]),
+        Some(VelocityComponent { vector: (1., 0.) })
+    );
+    assert_eq!(ecs.remove_velocity_from_mob(&ids[1]), None);
+    ecs.delete_mob(&ids[0]);
+    for (i, id) in ids.iter().enumerate().skip(2) {
+        assert_eq!(
+            ecs.get_velocity_of_mob(id),
+            Some(&VelocityComponent { vector: (i as f64, 0.) })
+        );
+    }
+
+    ecs.add_velocity_to_mob(&ids[1], VelocityComponent { vector: (10., 0.) });
+    ecs.add_velocity_to_mob(&ids[4], VelocityComponent { vector: (40., 0.) });
+    assert_eq!(ecs.get_velocity_of_mob(&ids[1]), Some(&VelocityComponent { vector: (10., 0.) }));
+    assert_eq!(ecs.get_velocity_of_mob(&ids[4]), Some(&VelocityComponent { vector: (40., 0.) }));
+    assert_eq!(ecs.get_velocity_of_mob(&ids[0]), None);
+}
+
+#[test]
+fn component_storage_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let mob_id = ecs.spawn_mob(entity::MobEntity {
+        position: Some(PositionComponent { x: 1., y: 0. }),
+        clone_position: Some(PositionComponent { x: 0., y: 1. }),
+        velocity: Some(VelocityComponent { vector: (1., 1.) })
+    })?;
+
+    ecs.add_clone_position_to_mob(&mob_id, PositionComponent { x: 5., y: 5. });
+    assert_eq!(ecs.remove_velocity_from_mob(&mob_id), Some(VelocityComponent { vector: (1., 1.) }));
+    let mob = ecs.get_mob(&mob_id).unwrap();
+    assert_eq!(mob.position, Some(&PositionComponent { x: 1., y: 0. }));
+    assert_eq!(mob.clone_position, Some(&PositionComponent { x: 5., y: 5. }));
+    assert_eq!(mob.velocity, None);
+
+    let deleted = ecs.delete_mob(&mob_id).unwrap();
+    assert_eq!(deleted.position, Some(PositionComponent { x: 1., y: 0. }));
+    assert_eq!(deleted.clone_position, Some(PositionComponent { x: 5., y: 5. }));
+    assert_eq!(ecs.get_position_of_mob(&mob_id), None);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::redundant_pattern_matching)]
 fn archtype_test() {
     let mut ecs = TestECS::new();
     let mob_id = id::MobEntityId::new(0);
@@ -208,4 +1027,104 @@ fn archtype_test() {
     }
     
     todo!("Add entities")
-}
\ No newline at end of file
+}
+
+fn particle(i: usize) -> entity::ParticleEntity {
+    entity::ParticleEntity {
+        position: PositionComponent { x: i as f64, y: 0. },
+        velocity: VelocityComponent { vector: (0., i as f64) },
+        lifetime: None
+    }
+}
+
+#[test]
+fn required_component_test() -> Result<(), ECSError> {
+    let mut ecs = TestECS::new();
+    let log = Arc::new(Mutex::new(vec![]));
+    let velocity_log = log.clone();
+    ecs.observe_velocity_of_particle(ECSComponentEvent::Added, move |particle_id, _| {
+        velocity_log.lock().unwrap().push(("velocity added", *particle_id.get_id()));
+    });
+    let velocity_log = log.clone();
+    ecs.observe_velocity_of_particle(ECSComponentEvent::Removed, move |particle_id, _| {
+        velocity_log.lock().unwrap().push(("velocity removed", *particle_id.get_id()));
+    });
+
+    let mut commands = ecs.commands();
+    let ids = (0..4).map(|i| commands.spawn_particle(particle(i))).collect::<Vec<_>>();
+    ecs.apply_commands(commands)?;
+
+    let deleted = ecs.delete_particle(&ids[1]).unwrap();
+    assert_eq!(deleted.position, PositionComponent { x: 1., y: 0. });
+    assert_eq!(deleted.velocity, VelocityComponent { vector: (0., 1.) });
+    assert!(ecs.get_particle(&ids[1]).is_none());
+    assert_eq!(ecs.get_position_of_particle(&ids[1]), None);
+    for i in [0, 2, 3] {
+        let particle = ecs.get_particle(&ids[i]).unwrap();
+        assert_eq!(particle.position, &PositionComponent { x: i as f64, y: 0. });
+        assert_eq!(particle.velocity, &VelocityComponent { vector: (0., i as f64) });
+        assert_eq!(particle.lifetime, None);
+    }
+
+    ecs.add_position_to_particle(&ids[3], PositionComponent { x: 30., y: 0. });
+    ecs.add_position_to_particle(&ids[1], PositionComponent { x: 10., y: 0. });
+    ecs.add_lifetime_to_particle(&ids[3], LifetimeComponent { frames: 60 });
+    assert_eq!(ecs.get_position_of_particle(&ids[3]), Some(&PositionComponent { x: 30., y: 0. }));
+    assert_eq!(ecs.get_position_of_particle(&ids[1]), None);
+    assert_eq!(ecs.get_lifetime_of_particle(&ids[3]), Some(&LifetimeComponent { frames: 60 }));
+
+    let id = ecs.create_particle(ids[1].clone(), particle(5), ECSEntityCreateConflictResolution::Error)?;
+    assert_eq!(ecs.get_position_of_particle(&id), Some(&PositionComponent { x: 5., y: 0. }));
+    // The Id of the deleted particle is of an older generation
+    ecs.add_position_to_particle(&ids[1], PositionComponent { x: 50., y: 0. });
+    assert_eq!(ecs.get_position_of_particle(&ids[1]), None);
+    assert_eq!(ecs.get_position_of_particle(&id), Some(&PositionComponent { x: 5., y: 0. }));
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            ("velocity added", 0),
+            ("velocity added", 1),
+            ("velocity added", 2),
+            ("velocity added", 3),
+            ("velocity removed", 1),
+            ("velocity added", 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn capacity_test() {
+    let mut ecs = TestECS::new();
+    ecs.reserve_mob(100);
+    ecs.reserve_particle(100);
+    let mut commands = ecs.commands();
+    let mobs = (0..100)
+        .map(|i| commands.spawn_mob(entity::MobEntity {
+            position: Some(PositionComponent { x: i as f64, y: 0. }),
+            clone_position: Some(PositionComponent { x: 0., y: i as f64 }),
+            velocity: Some(VelocityComponent { vector: (i as f64, 0.) })
+        }))
+        .collect::<Vec<_>>();
+    let particles = (0..100).map(|i| commands.spawn_particle(particle(i))).collect::<Vec<_>>();
+    ecs.apply_commands(commands).unwrap();
+
+    for i in 10..100 {
+        ecs.delete_mob(&mobs[i]);
+        ecs.delete_particle(&particles[i]);
+    }
+    ecs.shrink_mob();
+    ecs.shrink_particle();
+    for i in 0..10 {
+        let mob = ecs.get_mob(&mobs[i]).unwrap();
+        assert_eq!(mob.position, Some(&PositionComponent { x: i as f64, y: 0. }));
+        assert_eq!(mob.clone_position, Some(&PositionComponent { x: 0., y: i as f64 }));
+        assert_eq!(mob.velocity, Some(&VelocityComponent { vector: (i as f64, 0.) }));
+        assert_eq!(
+            ecs.get_position_of_particle(&particles[i]),
+            Some(&PositionComponent { x: i as f64, y: 0. })
+        );
+    }
+    assert_eq!(ecs.get_position_of_mob(&mobs[99]), None);
+    assert_eq!(ecs.get_position_of_particle(&particles[99]), None);
+}
@@ -0,0 +1,47 @@
+use qoecs::*;
+
+#[derive(PartialEq, Debug)]
+pub struct Config {
+    name: String
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct PositionComponent {
+    x: f64,
+    y: f64
+}
+
+create_ecs!(
+    ConfigECS,
+    Resources(config => crate::Config, step => u64 = 16, time => u64),
+    ArrayResources(seq => [u64; 4] = [1; 4], names => [u64; 4]),
+    MapResources(),
+    OptionalResources(),
+    VecResources(),
+    RingResources(),
+    LockedResources(),
+    Events(),
+    Archtypes(
+        Entity(
+            mob,
+            Components(
+                position => crate::PositionComponent
+            )
+        )
+    ),
+    LockedArchtypes(),
+    Systems()
+);
+
+#[test]
+fn new_with_test() -> Result<(), ECSError> {
+    let ecs = ConfigECS::new_with(Config { name: "A".to_owned() }, 3);
+    assert_eq!(ecs.get_resource_config(), &Config { name: "A".to_owned() });
+    assert_eq!(ecs.get_resource_step(), &16);
+    assert_eq!(ecs.get_resource_time(), &3);
+    for i in 0..4 {
+        assert_eq!(ecs.get_resource_seq(i)?, &1);
+        assert_eq!(ecs.get_resource_names(i)?, &0);
+    }
+    Ok(())
+}
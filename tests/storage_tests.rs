@@ -0,0 +1,75 @@
+use qoecs::*;
+
+#[test]
+fn sparse_set_test() {
+    let mut set = ECSSparseSet::new();
+    assert!(ECSComponentStorage::is_empty(&set));
+    assert_eq!(ECSComponentStorage::insert(&mut set, 3, 'a'), None);
+    assert_eq!(ECSComponentStorage::insert(&mut set, 7, 'b'), None);
+    assert_eq!(ECSComponentStorage::insert(&mut set, 1, 'c'), None);
+    assert_eq!(ECSComponentStorage::insert(&mut set, 7, 'd'), Some('b'));
+    assert_eq!(ECSComponentStorage::len(&set), 3);
+    assert_eq!(set.values(), &['a', 'd', 'c']);
+
+    // The last value is swapped into the hole of the removed one
+    assert_eq!(ECSComponentStorage::remove(&mut set, 3), Some('a'));
+    assert_eq!(ECSComponentStorage::remove(&mut set, 3), None);
+    assert_eq!(set.values(), &['c', 'd']);
+    assert_eq!(set.index(1), Some(0));
+    assert_eq!(set.index(3), None);
+    assert_eq!(ECSComponentStorage::get(&set, 1), Some(&'c'));
+    assert_eq!(ECSComponentStorage::get(&set, 7), Some(&'d'));
+
+    // Reinserting after a swap remove does not disturb the moved value
+    assert_eq!(ECSComponentStorage::insert(&mut set, 3, 'e'), None);
+    assert_eq!(ECSComponentStorage::get(&set, 1), Some(&'c'));
+    assert_eq!(ECSComponentStorage::get(&set, 3), Some(&'e'));
+    *ECSComponentStorage::get_mut(&mut set, 7).unwrap() = 'f';
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![(1, &'c'), (7, &'f'), (3, &'e')]);
+
+    assert_eq!(ECSComponentStorage::remove(&mut set, 3), Some('e'));
+    ECSComponentStorage::shrink_to_fit(&mut set);
+    assert_eq!(set.iter().collect::<Vec<_>>(), vec![(1, &'c'), (7, &'f')]);
+}
+
+#[test]
+fn dense_storage_test() {
+    let mut storage = ECSDenseStorage::new();
+    assert_eq!(ECSComponentStorage::insert(&mut storage, 5, 'a'), None);
+    assert_eq!(ECSComponentStorage::insert(&mut storage, 2, 'b'), None);
+    assert_eq!(ECSComponentStorage::insert(&mut storage, 5, 'c'), Some('a'));
+    assert_eq!(ECSComponentStorage::len(&storage), 2);
+    assert_eq!(storage.iter().collect::<Vec<_>>(), vec![(2, &'b'), (5, &'c')]);
+
+    assert_eq!(ECSComponentStorage::remove(&mut storage, 2), Some('b'));
+    assert_eq!(ECSComponentStorage::remove(&mut storage, 2), None);
+    assert_eq!(ECSComponentStorage::remove(&mut storage, 100), None);
+    assert_eq!(ECSComponentStorage::insert(&mut storage, 2, 'd'), None);
+    for (_, value) in storage.iter_mut() {
+        value.make_ascii_uppercase();
+    }
+    assert_eq!(storage.iter().collect::<Vec<_>>(), vec![(2, &'D'), (5, &'C')]);
+
+    assert_eq!(ECSComponentStorage::remove(&mut storage, 5), Some('C'));
+    ECSComponentStorage::shrink_to_fit(&mut storage);
+    assert_eq!(ECSComponentStorage::get(&storage, 2), Some(&'D'));
+    assert_eq!(ECSComponentStorage::get(&storage, 5), None);
+}
+
+#[test]
+fn large_id_storage_test() {
+    let mut set = ECSSparseSet::new();
+    let mut storage = ECSDenseStorage::new();
+    ECSComponentStorage::insert(&mut set, ECS_MAX_ENTITY_ID, 1);
+    ECSComponentStorage::insert(&mut storage, 1 << 24, 2);
+    assert_eq!(ECSComponentStorage::get(&set, ECS_MAX_ENTITY_ID), Some(&1));
+    assert_eq!(ECSComponentStorage::get(&storage, 1 << 24), Some(&2));
+    assert_eq!(ECSComponentStorage::get(&set, usize::MAX), None);
+    assert_eq!(ECSComponentStorage::get(&storage, usize::MAX), None);
+}
+
+#[test]
+#[should_panic(expected = "above `ECS_MAX_ENTITY_ID`")]
+fn out_of_range_storage_test() {
+    ECSComponentStorage::insert(&mut ECSSparseSet::new(), usize::MAX, ());
+}
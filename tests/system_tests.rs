@@ -0,0 +1,321 @@
+use qoecs::*;
+
+#[derive(PartialEq, Clone, Debug)]
+pub struct PositionComponent {
+    x: f64,
+    y: f64
+}
+
+fn seed(_ecs: &SystemECS, commands: &mut command::Commands) {
+    commands.write_resource_names(0, "origin".to_owned());
+    commands.spawn_mob(entity::MobEntity { position: Some(PositionComponent { x: 0.0, y: 0.0 }) });
+}
+
+fn count_frames(ecs: &SystemECS, commands: &mut command::Commands) {
+    commands.write_resource_frames(ecs.get_resource_frames() + 1);
+}
+
+fn count_mobs(ecs: &SystemECS, _commands: &mut command::Commands) {
+    let mobs = if ecs.get_resource_names(0).is_some() { 1 } else { 0 };
+    *ecs.lock_resource_mobs() += mobs;
+}
+
+#[derive(Default)]
+pub struct Cooldown {
+    runs: u32
+}
+
+fn tick_cooldown(ecs: &SystemECS, _commands: &mut command::Commands, cooldown: &mut Cooldown) {
+    cooldown.runs += 1;
+    if cooldown.runs == 3 {
+        cooldown.runs = 0;
+        *ecs.lock_resource_cooldowns() += 1;
+    }
+}
+
+fn snapshot_frames(ecs: &mut SystemECS, snapshots: &mut usize) {
+    let frames = *ecs.get_resource_frames();
+    ecs.push_resource_history(frames);
+    *snapshots += 1;
+    assert_eq!(ecs.len_resource_history(), *snapshots);
+}
+
+fn write_slot(ecs: &SystemECS, commands: &mut command::Commands) {
+    if let Some(slot) = ecs.get_resource_slot() {
+        commands.write_resource_slots(1, *slot);
+    }
+}
+
+fn check_frames(ecs: &SystemECS, _commands: &mut command::Commands) -> Result<(), ECSSystemError> {
+    match ecs.get_resource_frame_limit() {
+        Some(limit) if ecs.get_resource_frames() > limit => {
+            Err(format!("{} frames over the limit of {}", ecs.get_resource_frames(), limit).into())
+        },
+        _ => Ok(())
+    }
+}
+
+fn flush(ecs: &SystemECS, _commands: &mut command::Commands) {
+    ecs.lock_resource_log().push(format!("flushed {} frames", ecs.get_resource_frames()));
+}
+
+create_ecs!(
+    SystemECS,
+    Resources(frames => u64),
+    ArrayResources(slots => [u64; 2]),
+    MapResources(names => <u64, String>),
+    OptionalResources(slot => usize, frame_limit => u64),
+    VecResources(history => u64, tracked => u64),
+    RingResources(),
+    LockedResources(mobs => u64 = 0, cooldowns => u64 = 0, log => Vec<String> = vec![]),
+    Events(alert => String),
+    Archtypes(
+        Entity(
+            mob,
+            Components(
+                position => crate::PositionComponent
+            )
+        )
+    ),
+    LockedArchtypes(),
+    Systems(
+        Startup(
+            System(seed => crate::seed)
+        ),
+        Stage(
+            System(count_frames => crate::count_frames, Reads(frames), Writes(frames)),
+            System(count_mobs => crate::count_mobs, Reads(names), Writes(mobs)),
+            System(tick_cooldown => crate::tick_cooldown, Writes(cooldowns), Local(crate::Cooldown))
+        ),
+        Exclusive(
+            System(snapshot_frames => crate::snapshot_frames, Reads(frames), Writes(history), Local(usize))
+        ),
+        Stage(
+            System(write_slot => crate::write_slot, Reads(slot), Writes(slots))
+        ),
+        Stage(
+            System(check_frames => crate::check_frames, Reads(frames, frame_limit))
+        ),
+        Shutdown(
+            System(flush => crate::flush)
+        )
+    )
+);
+
+#[test]
+fn startup_shutdown_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    assert!(ecs.get_resource_names(0).is_none());
+
+    // Startup systems run once, before the first Stage
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.get_resource_names(0), Some(&"origin".to_owned()));
+    assert!(ecs.has_mob(&id::MobEntityId::new(0)));
+    assert_eq!(ecs.get_resource_frames(), &1);
+    assert_eq!(*ecs.read_resource_mobs(), 1);
+
+    ecs.delete_mob(&id::MobEntityId::new(0));
+    assert!(ecs.run_systems().is_ok());
+    assert!(ecs.run_systems().is_ok());
+    assert!(!ecs.has_mob(&id::MobEntityId::new(0)));
+    assert_eq!(ecs.get_resource_frames(), &3);
+    assert_eq!(*ecs.read_resource_mobs(), 3);
+    assert!(ecs.read_resource_log().is_empty());
+
+    assert!(ecs.shutdown().is_ok());
+    assert_eq!(*ecs.read_resource_log(), vec!["flushed 3 frames".to_owned()]);
+    Ok(())
+}
+
+#[test]
+fn system_registry_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    let systems = ecs.systems();
+    assert_eq!(
+        systems.iter().map(|system| (system.name, system.schedule, system.stage)).collect::<Vec<_>>(),
+        vec![
+            ("seed", ECSSchedule::Startup, 0),
+            ("count_frames", ECSSchedule::Update, 1),
+            ("count_mobs", ECSSchedule::Update, 1),
+            ("tick_cooldown", ECSSchedule::Update, 1),
+            ("snapshot_frames", ECSSchedule::Update, 2),
+            ("write_slot", ECSSchedule::Update, 3),
+            ("check_frames", ECSSchedule::Update, 4),
+            ("flush", ECSSchedule::Shutdown, 5)
+        ]
+    );
+    assert_eq!(systems[2].reads, &["names"]);
+    assert_eq!(systems[2].writes, &["mobs"]);
+    assert_eq!(systems[6].reads, &["frames", "frame_limit"]);
+    assert!(systems[6].writes.is_empty());
+    assert_eq!(
+        systems.iter().filter(|system| system.exclusive).map(|system| system.name).collect::<Vec<_>>(),
+        vec!["snapshot_frames"]
+    );
+    assert!(systems[0].reads.is_empty());
+    assert!(systems.iter().all(|system| system.enabled));
+
+    // Disabled systems are skipped, Startup ones included
+    ecs.set_system_enabled("seed", false)?;
+    ecs.set_system_enabled("count_frames", false)?;
+    assert!(ecs.run_systems().is_ok());
+    assert!(ecs.get_resource_names(0).is_none());
+    assert_eq!(ecs.get_resource_frames(), &0);
+    assert_eq!(*ecs.read_resource_mobs(), 0);
+    assert!(!ecs.systems()[1].enabled);
+
+    ecs.set_system_enabled("count_frames", true)?;
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.get_resource_frames(), &1);
+    assert!(matches!(
+        ecs.set_system_enabled("missing", true),
+        Err(ECSError::SystemNotFoundError)
+    ));
+    Ok(())
+}
+
+#[test]
+fn fallible_system_test() {
+    let mut ecs = SystemECS::new();
+    ecs.write_resource_slot(5);
+    ecs.write_resource_frame_limit(1);
+
+    // Stages after the stage of a failed system do not run
+    let report = ecs.run_systems();
+    assert!(!report.is_ok());
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].system, "write_slot");
+    assert_eq!(report.failures[0].tick, 1);
+    assert!(matches!(
+        report.failures[0].error.downcast_ref::<ECSError>(),
+        Some(ECSError::ArrayResourceWriteOutOfBoundsError)
+    ));
+
+    ecs.set_system_error_policy(ECSSchedule::Update, ECSErrorPolicy::Continue);
+    let report = ecs.run_systems();
+    assert_eq!(ecs.system_tick(), 2);
+    assert_eq!(
+        report.failures.iter().map(|failure| (failure.system, failure.tick)).collect::<Vec<_>>(),
+        vec![("write_slot", 2), ("check_frames", 2)]
+    );
+    assert_eq!(report.failures[1].error.to_string(), "2 frames over the limit of 1");
+
+    // Logged failures are reported too
+    ecs.set_system_error_policy(ECSSchedule::Update, ECSErrorPolicy::Log);
+    ecs.write_resource_slot(0);
+    let report = ecs.run_systems();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].system, "check_frames");
+    assert_eq!(ecs.get_resource_slots(0).unwrap(), &1);
+    assert!(ecs.shutdown().is_ok());
+}
+
+#[test]
+#[cfg(feature = "system-stats")]
+fn system_stats_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    assert_eq!(ecs.system_stats("count_frames")?, ECSSystemStats::default());
+
+    ecs.set_system_budget(Some(std::time::Duration::ZERO));
+    ecs.set_system_enabled("count_mobs", false)?;
+    for _ in 0..3 {
+        assert!(ecs.run_systems().is_ok());
+    }
+    let stats = ecs.system_stats("count_frames")?;
+    assert_eq!(stats.calls, 3);
+    assert!(stats.max >= stats.last);
+    assert!(stats.max >= stats.average);
+    assert_eq!(ecs.system_stats("seed")?.calls, 1);
+    assert_eq!(ecs.system_stats("count_mobs")?.calls, 0);
+    assert!(ecs.system_stats("missing").is_err());
+
+    let table = ecs.system_stats_table();
+    let lines = table.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 9);
+    assert!(lines[0].starts_with("System          | "));
+    assert!(lines[2].starts_with("count_frames    |        3 | "));
+    Ok(())
+}
+
+#[test]
+fn exclusive_system_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    // Exclusive systems run after the Commands of the stages before them
+    assert!(ecs.run_systems().is_ok());
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.iter_resource_history().collect::<Vec<_>>(), vec![&1, &2]);
+
+    ecs.set_system_enabled("snapshot_frames", false)?;
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.len_resource_history(), 2);
+    Ok(())
+}
+
+#[test]
+fn system_local_state_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    for _ in 0..7 {
+        assert!(ecs.run_systems().is_ok());
+    }
+    assert_eq!(*ecs.read_resource_cooldowns(), 2);
+
+    // Local state is kept while its system is disabled
+    ecs.set_system_enabled("tick_cooldown", false)?;
+    assert!(ecs.run_systems().is_ok());
+    ecs.set_system_enabled("tick_cooldown", true)?;
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(*ecs.read_resource_cooldowns(), 2);
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(*ecs.read_resource_cooldowns(), 3);
+    assert_eq!(ecs.len_resource_history(), 10);
+    Ok(())
+}
+
+#[test]
+fn runtime_system_test() -> Result<(), ECSError> {
+    let mut ecs = SystemECS::new();
+    ecs.add_system("track_mobs", |frames: system::Res<resource::Frames>, mobs: system::Query<archetype::Mob>, commands: &mut command::Commands| {
+        let moved = mobs.iter().filter(|(_, mob)| mob.position.is_some_and(|position| position.x > 0.0)).count();
+        commands.push_resource_tracked(*frames * 10 + moved as u64);
+    })?;
+    ecs.add_system("log_alerts", |mut alerts: system::Reader<event::Alert>, commands: &mut command::Commands| {
+        for alert in alerts.read() {
+            if alert.is_empty() {
+                return Err(ECSSystemError::from("empty alert"));
+            }
+            commands.push_resource_tracked(alert.len() as u64);
+        }
+        Ok(())
+    })?;
+    assert!(matches!(
+        ecs.add_system("seed", || {}),
+        Err(ECSError::SystemNameInUseError)
+    ));
+
+    // Runtime systems run after the declared stages, seeing their Commands
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.iter_resource_tracked().collect::<Vec<_>>(), vec![&10]);
+    let mob = ecs.spawn_mob(entity::MobEntity { position: Some(PositionComponent { x: 1.0, y: 0.0 }) })?;
+    ecs.send_alert("spawned".to_owned());
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.iter_resource_tracked().collect::<Vec<_>>(), vec![&10, &21, &7]);
+    assert_eq!(ecs.systems()[8].name, "track_mobs");
+    assert_eq!(ecs.systems()[8].stage, 6);
+
+    // Events are read once, and the Commands of a failed system are dropped
+    ecs.set_system_enabled("track_mobs", false)?;
+    ecs.send_alert("moved".to_owned());
+    ecs.send_alert(String::new());
+    let report = ecs.run_systems();
+    assert_eq!(report.failures.len(), 1);
+    assert_eq!(report.failures[0].system, "log_alerts");
+    assert_eq!(ecs.len_resource_tracked(), 3);
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.len_resource_tracked(), 3);
+
+    ecs.delete_mob(&mob);
+    ecs.set_system_enabled("track_mobs", true)?;
+    assert!(ecs.run_systems().is_ok());
+    assert_eq!(ecs.iter_resource_tracked().last(), Some(&50));
+    Ok(())
+}